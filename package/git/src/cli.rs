@@ -1,4 +1,4 @@
-use clap::{arg, command, value_parser, Parser, Subcommand};
+use clap::{value_parser, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 /// Git 工具集
@@ -39,15 +39,33 @@ pub struct Cli {
 
     /// 代理地址
     ///
-    /// 设置HTTP代理地址，用于访问远程Git仓库。
-    /// 例如：`giter --proxy http://127.0.0.1:7890 branch`
+    /// 设置代理地址，用于访问远程Git仓库，支持 http、https、socks5 和 socks5h。
+    /// 不指定时依次使用 git 配置中的 `http.proxy` 和 `HTTPS_PROXY` 等环境变量，
+    /// 并遵循 `NO_PROXY`。
+    /// 例如：`giter --proxy socks5://127.0.0.1:7890 tag`
     #[arg(
         short = 'x',
         long = "proxy",
+        global = true,
         value_name = "URL",
         help_heading = "网络设置"
     )]
     pub proxy: Option<String>,
+
+    /// 输出格式
+    ///
+    /// 以机器可读的格式输出结果（不包含颜色代码），便于脚本和 CI 使用。
+    /// 不指定时以表格或列表形式显示。
+    /// 例如：`giter --format json branch`
+    #[arg(
+        short = 'f',
+        long = "format",
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        help_heading = "显示格式"
+    )]
+    pub format: Option<OutputFormat>,
 }
 
 /// Git仓库操作命令
@@ -73,9 +91,19 @@ pub enum Commands {
     ///
     /// # 只显示远程分支
     /// giter branch --remote
+    ///
+    /// # 按最后提交时间排序
+    /// giter branch --sort date
+    ///
+    /// # 预览可清理的分支
+    /// giter branch prune
     /// ```
     #[command(visible_alias = "br")]
     Branch {
+        /// 分支操作
+        #[command(subcommand)]
+        command: Option<BranchCommands>,
+
         /// 以列表形式显示
         ///
         /// 将分支信息以简洁的列表形式展示，每行显示一个分支。
@@ -110,12 +138,27 @@ pub enum Commands {
         /// 仅显示远程仓库中的分支，不包含本地分支。
         #[arg(long = "remote", conflicts_with = "local", help_heading = "筛选选项")]
         remote: bool,
+
+        /// 排序方式
+        ///
+        /// name: HEAD 分支优先，其他按字母顺序（默认）；
+        /// date: 按最后提交时间从新到旧；
+        /// author: 按最后提交作者。
+        #[arg(
+            short = 's',
+            long = "sort",
+            value_enum,
+            default_value_t = BranchSort::Name,
+            help_heading = "显示格式"
+        )]
+        sort: BranchSort,
     },
 
     /// 标签管理
     ///
     /// 查看和管理Git仓库中的标签。
-    /// 可以以表格或列表形式显示所有标签。
+    /// 可以以表格或列表形式显示，远程标签通过连接各个远程仓库获取，
+    /// 并标记仅本地、仅远程或提交不一致的标签。
     ///
     /// 示例:
     ///
@@ -125,9 +168,29 @@ pub enum Commands {
     ///
     /// # 以列表形式显示所有标签
     /// giter tag --list
+    ///
+    /// # 只显示本地标签
+    /// giter tag --local
+    ///
+    /// # 只显示远程标签
+    /// giter tag --remote
+    ///
+    /// # 创建带注释的标签并推送
+    /// giter tag create v1.0.0 -m "首次发布"
+    /// giter tag push v1.0.0
+    ///
+    /// # 只显示 ziper-v 前缀的标签
+    /// giter tag --prefix ziper-v
+    ///
+    /// # 基于最新版本创建下一个 patch 版本标签
+    /// giter tag bump patch --prefix ziper-v
     /// ```
     #[command(visible_alias = "t")]
     Tag {
+        /// 标签操作
+        #[command(subcommand)]
+        command: Option<TagCommands>,
+
         /// 以列表形式显示
         ///
         /// 将标签信息以简洁的列表形式展示，每行显示一个标签。
@@ -150,9 +213,331 @@ pub enum Commands {
             help_heading = "显示格式"
         )]
         table: bool,
+
+        /// 只显示本地标签
+        ///
+        /// 仅显示本地仓库中的标签，不连接远程仓库，同步状态显示为“未检查”。
+        #[arg(long = "local", conflicts_with = "remote", help_heading = "筛选选项")]
+        local: bool,
+
+        /// 只显示远程标签
+        ///
+        /// 仅显示各远程仓库中的标签，不包含本地标签。
+        #[arg(long = "remote", conflicts_with = "local", help_heading = "筛选选项")]
+        remote: bool,
+
+        /// 版本前缀
+        ///
+        /// 只处理 `<前缀><版本号>` 形式的标签，例如 `v`、`ziper-v`。
+        /// 标签按语义化版本排序，同一前缀的标签排在一起。
+        #[arg(
+            long = "prefix",
+            value_name = "PREFIX",
+            global = true,
+            help_heading = "筛选选项"
+        )]
+        prefix: Option<String>,
+    },
+
+    /// 生成变更日志
+    ///
+    /// 遍历两个引用之间的提交，按 Conventional Commits 类型（feat、fix、chore 等）
+    /// 分组，默认输出 Markdown，可通过 `--format json` 等输出机器可读格式。
+    /// 未指定 `--from` 时从 `--to` 之前的上一个版本标签开始。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 上一个版本标签到 HEAD 之间的变更
+    /// giter changelog
+    ///
+    /// # 为 ziper-v0.1.2 生成发布说明
+    /// giter changelog --to ziper-v0.1.2
+    ///
+    /// # 指定范围并输出 JSON
+    /// giter changelog --from v1.0.0 --to v1.1.0 --format json
+    /// ```
+    #[command(visible_alias = "cl")]
+    Changelog {
+        /// 起始引用（不包含），默认为上一个版本标签
+        #[arg(long = "from", value_name = "REF")]
+        from: Option<String>,
+
+        /// 结束引用（包含）
+        #[arg(long = "to", value_name = "REF", default_value = "HEAD")]
+        to: String,
+
+        /// 查找上一个版本标签时使用的前缀，例如 `ziper-v`
+        ///
+        /// `--to` 为版本标签时默认使用其前缀，否则使用离 `--to` 最近的版本标签的前缀。
+        #[arg(long = "prefix", value_name = "PREFIX")]
+        prefix: Option<String>,
+    },
+
+    /// 交互式切换分支
+    ///
+    /// 打开终端界面列出本地和远程分支，输入内容进行模糊筛选，
+    /// 右侧预览选中分支的最近提交，按 Enter 切换。
+    /// 选择仅存在于远程的分支时，会创建同名的本地分支并跟踪该远程分支。
+    /// 工作区有未提交的修改时拒绝切换，除非指定 `--stash` 或 `--force`。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 打开分支选择界面
+    /// giter switch
+    ///
+    /// # 以 feat 作为初始筛选条件
+    /// giter switch feat
+    ///
+    /// # 切换前自动储藏未提交的修改
+    /// giter switch --stash
+    /// ```
+    #[command(visible_alias = "sw")]
+    Switch {
+        /// 初始筛选条件
+        query: Option<String>,
+
+        /// 切换前储藏未提交的修改（之后可用 `git stash pop` 恢复）
+        #[arg(long = "stash", conflicts_with = "force")]
+        stash: bool,
+
+        /// 强制切换，丢弃工作区中未提交的修改
+        #[arg(long = "force", conflicts_with = "stash")]
+        force: bool,
     },
 }
 
+/// 分支操作命令
+#[derive(Subcommand)]
+pub enum BranchCommands {
+    /// 清理分支
+    ///
+    /// 查找已合并到基准分支、上游已删除或长期未更新的本地分支。
+    /// 默认只显示清理计划，使用 `--yes` 才会真正删除。
+    /// 当前所在的分支永远不会被删除。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 预览可清理的分支
+    /// giter branch prune
+    ///
+    /// # 指定基准分支，并包含 90 天未更新的分支
+    /// giter branch prune --base origin/develop --stale-days 90
+    ///
+    /// # 执行删除
+    /// giter branch prune --yes
+    /// ```
+    Prune {
+        /// 基准分支
+        ///
+        /// 合并到该分支的本地分支会被清理。
+        /// 默认使用远程 HEAD 指向的默认分支（如 origin/main）。
+        #[arg(short = 'b', long = "base", value_name = "BRANCH")]
+        base: Option<String>,
+
+        /// 清理超过指定天数没有新提交的分支
+        #[arg(long = "stale-days", value_name = "DAYS")]
+        stale_days: Option<u64>,
+
+        /// 只显示清理计划，不删除（默认）
+        #[arg(long = "dry-run", conflicts_with = "yes")]
+        dry_run: bool,
+
+        /// 确认删除分支
+        #[arg(short = 'y', long = "yes", conflicts_with = "dry_run")]
+        yes: bool,
+
+        /// 以列表形式显示
+        #[arg(short = 'l', long = "list", help_heading = "显示格式")]
+        list: bool,
+    },
+}
+
+/// 标签操作命令
+#[derive(Subcommand)]
+pub enum TagCommands {
+    /// 创建标签
+    ///
+    /// 指定 `--message` 时创建带注释的标签，否则创建轻量标签。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 在 HEAD 上创建轻量标签
+    /// giter tag create v1.0.0
+    ///
+    /// # 在指定提交上创建带注释的标签
+    /// giter tag create v1.0.0 -m "首次发布" --commit a1b2c3d
+    /// ```
+    Create {
+        /// 标签名
+        name: String,
+
+        /// 标签说明，指定时创建带注释的标签
+        #[arg(short = 'm', long = "message")]
+        message: Option<String>,
+
+        /// 标签指向的提交（默认为 HEAD）
+        #[arg(short = 'c', long = "commit", value_name = "COMMIT")]
+        commit: Option<String>,
+
+        /// 覆盖已存在的同名标签
+        #[arg(long = "force")]
+        force: bool,
+    },
+
+    /// 删除标签
+    ///
+    /// 默认只删除本地标签；指定 `--remote` 时同时删除远程标签。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 删除本地标签
+    /// giter tag delete v1.0.0
+    ///
+    /// # 同时删除 origin 上的标签
+    /// giter tag delete -r v1.0.0
+    ///
+    /// # 同时删除 upstream 上的标签
+    /// giter tag delete v1.0.0 --remote --remote-name upstream
+    /// ```
+    Delete {
+        /// 标签名
+        name: String,
+
+        /// 同时删除远程标签
+        #[arg(short = 'r', long = "remote")]
+        remote: bool,
+
+        /// 删除远程标签时使用的远程仓库名（默认为 origin）
+        #[arg(long = "remote-name", value_name = "REMOTE", requires = "remote")]
+        remote_name: Option<String>,
+    },
+
+    /// 推送标签
+    ///
+    /// 推送指定标签或所有本地标签到远程仓库。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 推送单个标签
+    /// giter tag push v1.0.0
+    ///
+    /// # 推送所有标签到 upstream
+    /// giter tag push --all --remote upstream
+    /// ```
+    Push {
+        /// 标签名
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+
+        /// 推送所有本地标签
+        #[arg(short = 'a', long = "all")]
+        all: bool,
+
+        /// 远程仓库名
+        #[arg(short = 'r', long = "remote", default_value = "origin")]
+        remote: String,
+
+        /// 强制推送，覆盖远程的同名标签
+        #[arg(long = "force")]
+        force: bool,
+    },
+
+    /// 显示最新版本标签
+    ///
+    /// 按语义化版本查找最新的本地标签，默认跳过预发布版本。
+    /// 仓库中存在多种前缀时需要通过 `--prefix` 指定。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// giter tag latest --prefix ziper-v
+    /// ```
+    Latest {
+        /// 包含预发布版本（如 1.0.0-rc.1）
+        #[arg(long = "pre")]
+        pre: bool,
+    },
+
+    /// 创建下一个版本标签
+    ///
+    /// 基于最新的版本标签计算下一个版本并创建标签，
+    /// 没有版本标签时从 0.0.0 开始。
+    /// 从预发布版本递增时与 npm 规则一致，例如 `1.1.0-rc.1` 递增 minor 得到 `1.1.0`。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # v1.2.3 -> v1.3.0
+    /// giter tag bump minor
+    ///
+    /// # ziper-v0.1.1 -> ziper-v0.1.2-rc.0
+    /// giter tag bump prerelease --preid rc --prefix ziper-v
+    ///
+    /// # 只显示将要创建的标签
+    /// giter tag bump patch --dry-run
+    /// ```
+    Bump {
+        /// 递增的版本级别
+        #[arg(value_enum)]
+        level: BumpLevel,
+
+        /// 预发布标识，例如 alpha、beta、rc
+        #[arg(long = "preid", value_name = "ID")]
+        preid: Option<String>,
+
+        /// 标签说明，指定时创建带注释的标签
+        #[arg(short = 'm', long = "message")]
+        message: Option<String>,
+
+        /// 标签指向的提交（默认为 HEAD）
+        #[arg(short = 'c', long = "commit", value_name = "COMMIT")]
+        commit: Option<String>,
+
+        /// 只显示将要创建的标签，不实际创建
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+    },
+}
+
+/// 机器可读的输出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+    Yaml,
+    Tsv,
+}
+
+/// 分支排序方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BranchSort {
+    /// 按名称排序，HEAD 分支优先
+    Name,
+    /// 按最后提交时间排序，最新的在前
+    Date,
+    /// 按最后提交作者排序
+    Author,
+}
+
+/// 版本号递增级别
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BumpLevel {
+    /// 主版本号：1.2.3 -> 2.0.0
+    Major,
+    /// 次版本号：1.2.3 -> 1.3.0
+    Minor,
+    /// 修订号：1.2.3 -> 1.2.4
+    Patch,
+    /// 预发布版本：1.2.3 -> 1.2.4-0，1.2.4-rc.0 -> 1.2.4-rc.1
+    Prerelease,
+}
+
 impl Cli {
     pub fn get_repo_path(&self) -> PathBuf {
         match &self.project {
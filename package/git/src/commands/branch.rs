@@ -1,7 +1,9 @@
 use anyhow::Result;
 use git2::{BranchType, Repository};
+use std::cmp::Ordering;
 
-use crate::models::BranchInfo;
+use crate::cli::BranchSort;
+use crate::models::{BranchInfo, CommitInfo};
 
 pub fn get_branch_info(repo: &Repository) -> Result<(Vec<BranchInfo>, Vec<BranchInfo>)> {
     let mut local_branches = Vec::new();
@@ -23,12 +25,45 @@ pub fn get_branch_info(repo: &Repository) -> Result<(Vec<BranchInfo>, Vec<Branch
         let name = branch.name()?.unwrap_or("").to_string();
         let is_head = name == head_name;
 
-        let upstream = branch
-            .upstream()
-            .ok()
-            .and_then(|b| b.name().ok().flatten().map(|s| s.to_owned()));
+        let mut branch_info = match branch.upstream() {
+            Ok(upstream) => {
+                let upstream_name = upstream.name().ok().flatten().map(|s| s.to_owned());
+                let mut info = BranchInfo::new(name, is_head, upstream_name);
+
+                // 计算与上游分支的领先/落后提交数
+                if let (Some(local_oid), Some(upstream_oid)) =
+                    (branch.get().target(), upstream.get().target())
+                {
+                    let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid)?;
+                    info.ahead = ahead;
+                    info.behind = behind;
+                }
+                info
+            }
+            Err(_) => BranchInfo::new(name, is_head, None),
+        };
 
-        let branch_info = BranchInfo::new(name, is_head, upstream);
+        // 配置了上游但上游引用已不存在，视为已删除
+        if branch_type == BranchType::Local && branch_info.upstream.is_none() {
+            if let Some(upstream_ref) = branch
+                .get()
+                .name()
+                .and_then(|refname| repo.branch_upstream_name(refname).ok())
+            {
+                if let Some(upstream_ref) = upstream_ref.as_str() {
+                    branch_info.upstream =
+                        Some(upstream_ref.trim_start_matches("refs/remotes/").to_string());
+                    branch_info.gone = true;
+                }
+            }
+        }
+
+        // 解析分支最新提交
+        branch_info.last_commit = branch
+            .get()
+            .peel_to_commit()
+            .ok()
+            .map(|commit| CommitInfo::from_commit(&commit));
 
         match branch_type {
             BranchType::Local => local_branches.push(branch_info),
@@ -36,19 +71,40 @@ pub fn get_branch_info(repo: &Repository) -> Result<(Vec<BranchInfo>, Vec<Branch
         }
     }
 
-    // 对本地分支进行排序：HEAD 分支优先，其他按字母顺序
-    local_branches.sort_by(|a, b| {
-        if a.is_head {
-            std::cmp::Ordering::Less
-        } else if b.is_head {
-            std::cmp::Ordering::Greater
-        } else {
-            a.name.cmp(&b.name)
-        }
-    });
-
-    // 对远程分支按字母顺序排序
-    remote_branches.sort_by(|a, b| a.name.cmp(&b.name));
+    sort_branches(&mut local_branches, BranchSort::Name);
+    sort_branches(&mut remote_branches, BranchSort::Name);
 
     Ok((local_branches, remote_branches))
 }
+
+/// 按指定字段对分支排序
+///
+/// - `name`: HEAD 分支优先，其他按字母顺序
+/// - `date`: 按最后提交时间从新到旧
+/// - `author`: 按最后提交作者，同一作者按时间从新到旧
+pub fn sort_branches(branches: &mut [BranchInfo], key: BranchSort) {
+    let time = |b: &BranchInfo| b.last_commit.as_ref().map(|c| c.time).unwrap_or(0);
+    let author = |b: &BranchInfo| {
+        b.last_commit
+            .as_ref()
+            .map(|c| c.author.to_lowercase())
+            .unwrap_or_default()
+    };
+
+    branches.sort_by(|a, b| match key {
+        BranchSort::Name => {
+            if a.is_head {
+                Ordering::Less
+            } else if b.is_head {
+                Ordering::Greater
+            } else {
+                a.name.cmp(&b.name)
+            }
+        }
+        BranchSort::Date => time(b).cmp(&time(a)).then_with(|| a.name.cmp(&b.name)),
+        BranchSort::Author => author(a)
+            .cmp(&author(b))
+            .then_with(|| time(b).cmp(&time(a)))
+            .then_with(|| a.name.cmp(&b.name)),
+    });
+}
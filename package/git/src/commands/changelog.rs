@@ -0,0 +1,170 @@
+use anyhow::{bail, Context, Result};
+use git2::{Oid, Repository, Sort};
+use std::collections::{HashMap, HashSet};
+
+use super::tag::get_local_tags;
+use crate::models::{Changelog, ChangelogEntry, CommitInfo, TagVersion};
+
+/// 生成两个引用之间的变更日志
+///
+/// 未指定 `from` 时使用 `to` 之前的上一个版本标签，没有版本标签时包含全部历史。
+/// 合并提交不会出现在变更日志中。
+pub fn generate_changelog(
+    repo: &Repository,
+    from: Option<&str>,
+    to: &str,
+    prefix: Option<&str>,
+) -> Result<Changelog> {
+    let to_commit = repo
+        .revparse_single(to)
+        .with_context(|| format!("找不到引用 {}", to))?
+        .peel_to_commit()
+        .with_context(|| format!("{} 不是一个提交", to))?;
+
+    let from = match from {
+        Some(from) => Some(from.to_string()),
+        None => find_previous_tag(repo, to, to_commit.id(), prefix)?,
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(to_commit.id())?;
+    if let Some(ref from) = from {
+        let from_oid = repo
+            .revparse_single(from)
+            .with_context(|| format!("找不到引用 {}", from))?
+            .peel_to_commit()
+            .with_context(|| format!("{} 不是一个提交", from))?
+            .id();
+        revwalk.hide(from_oid)?;
+    }
+
+    let mut entries = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let message = commit.message().unwrap_or("").to_string();
+        entries.push(ChangelogEntry::parse(
+            CommitInfo::from_commit(&commit),
+            &message,
+        ));
+    }
+
+    let version = version_tag(repo, to).map(|_| to.to_string());
+    let time = to_commit.committer().when().seconds();
+
+    Ok(Changelog::new(from, to.to_string(), version, time, entries))
+}
+
+/// `name` 是本地版本标签时返回解析后的版本
+fn version_tag(repo: &Repository, name: &str) -> Option<TagVersion> {
+    repo.find_reference(&format!("refs/tags/{}", name))
+        .ok()
+        .and_then(|_| TagVersion::parse(name))
+}
+
+/// 查找 `to` 之前的上一个版本标签
+///
+/// 只考虑 `to` 的祖先提交上的标签。`to` 本身是版本标签时沿用其前缀，
+/// 并且正式版本会跳过预发布标签，例如 `v1.2.0` 的上一个版本是 `v1.1.0`
+/// 而不是 `v1.2.0-rc.1`。未指定前缀时使用离 `to` 最近的版本标签的前缀。
+fn find_previous_tag(
+    repo: &Repository,
+    to: &str,
+    to_oid: Oid,
+    prefix: Option<&str>,
+) -> Result<Option<String>> {
+    let target = version_tag(repo, to);
+    let include_pre = target.as_ref().is_none_or(|v| !v.version.pre.is_empty());
+
+    let mut tags: HashMap<Oid, Vec<(TagVersion, String)>> = HashMap::new();
+    for tag in get_local_tags(repo)? {
+        if let Some(version) = TagVersion::parse(&tag.name) {
+            tags.entry(Oid::from_str(&tag.commit)?)
+                .or_default()
+                .push((version, tag.name));
+        }
+    }
+
+    let prefix = match prefix.or(target.as_ref().map(|v| v.prefix.as_str())) {
+        Some(prefix) => prefix.to_string(),
+        None => match nearest_prefix(repo, to_oid, &tags)? {
+            Some(prefix) => prefix,
+            None => return Ok(None),
+        },
+    };
+
+    let mut candidates = Vec::new();
+    for (oid, versions) in tags {
+        for (version, name) in versions {
+            if version.prefix != prefix
+                || (!include_pre && !version.version.pre.is_empty())
+                || target
+                    .as_ref()
+                    .is_some_and(|t| version.version >= t.version)
+            {
+                continue;
+            }
+
+            if oid != to_oid && repo.graph_descendant_of(to_oid, oid)? {
+                candidates.push((version, name));
+            }
+        }
+    }
+
+    Ok(candidates
+        .into_iter()
+        .max_by(|(a, _), (b, _)| a.version.cmp(&b.version))
+        .map(|(_, name)| name))
+}
+
+/// 查找离 `to` 最近的版本标签所使用的前缀
+///
+/// 从 `to` 开始按提交距离逐层向上查找，同一距离上出现多种前缀时返回错误。
+/// `to` 的祖先提交上没有版本标签时返回 None。
+fn nearest_prefix(
+    repo: &Repository,
+    to_oid: Oid,
+    tags: &HashMap<Oid, Vec<(TagVersion, String)>>,
+) -> Result<Option<String>> {
+    let mut visited = HashSet::from([to_oid]);
+    let mut layer = vec![to_oid];
+
+    while !layer.is_empty() {
+        let mut prefixes: Vec<&str> = layer
+            .iter()
+            .filter_map(|oid| tags.get(oid))
+            .flatten()
+            .map(|(version, _)| version.prefix.as_str())
+            .collect();
+        prefixes.sort_unstable();
+        prefixes.dedup();
+
+        match prefixes.as_slice() {
+            [] => {}
+            [prefix] => return Ok(Some(prefix.to_string())),
+            _ => bail!(
+                "最近的版本标签有多种前缀，请使用 --prefix 或 --from 指定起始标签: {}",
+                prefixes
+                    .iter()
+                    .map(|p| format!("\"{}\"", p))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+
+        let mut next = Vec::new();
+        for oid in layer {
+            for parent in repo.find_commit(oid)?.parent_ids() {
+                if visited.insert(parent) {
+                    next.push(parent);
+                }
+            }
+        }
+        layer = next;
+    }
+
+    Ok(None)
+}
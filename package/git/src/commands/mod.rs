@@ -1,5 +1,18 @@
 mod branch;
+mod changelog;
+mod proxy;
+mod prune;
+mod remote;
+mod socks;
+mod switch;
 mod tag;
 
-pub use branch::get_branch_info;
-pub use tag::get_tag_info;
+pub use branch::{get_branch_info, sort_branches};
+pub use changelog::generate_changelog;
+pub use prune::{delete_branches, find_prune_candidates, resolve_base};
+pub use socks::shutdown_bridges;
+pub use switch::{checkout_branch, is_worktree_dirty, recent_commits, stash_changes};
+pub use tag::{
+    create_tag, delete_remote_tag, delete_tag, find_latest_version, get_tag_info, next_tag_version,
+    push_tags,
+};
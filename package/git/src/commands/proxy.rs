@@ -0,0 +1,148 @@
+use anyhow::{bail, Context, Result};
+use git2::{ProxyOptions, Remote, Repository};
+use url::Url;
+
+use super::socks;
+
+/// 为远程仓库解析代理并生成 ProxyOptions
+///
+/// 代理按以下顺序查找：
+///
+/// 1. 命令行 `--proxy` 参数
+/// 2. git 配置 `remote.<name>.proxy` 和 `http.proxy`
+/// 3. 环境变量 `HTTPS_PROXY` / `HTTP_PROXY` / `ALL_PROXY`
+///
+/// 通过配置和环境变量得到的代理会遵循 `NO_PROXY`。
+/// 只有 http(s) 协议的远程仓库会使用代理，ssh 和本地路径直接连接。
+pub fn proxy_options(
+    repo: &Repository,
+    remote: &Remote,
+    proxy: Option<&str>,
+) -> Result<ProxyOptions<'static>> {
+    let mut options = ProxyOptions::new();
+
+    let Some(remote_url) = remote.url().and_then(|url| Url::parse(url).ok()) else {
+        return Ok(options);
+    };
+    if !matches!(remote_url.scheme(), "http" | "https") {
+        return Ok(options);
+    }
+
+    let proxy = match proxy {
+        Some(proxy) => Some(proxy.to_string()),
+        None => find_configured_proxy(repo, remote.name(), &remote_url),
+    };
+
+    if let Some(proxy) = proxy.filter(|p| !p.is_empty()) {
+        options.url(&effective_proxy_url(&proxy)?);
+    }
+
+    Ok(options)
+}
+
+fn find_configured_proxy(
+    repo: &Repository,
+    remote_name: Option<&str>,
+    remote_url: &Url,
+) -> Option<String> {
+    let host = remote_url.host_str().unwrap_or("");
+    let port = remote_url.port_or_known_default();
+    if is_no_proxy(host, port) {
+        return None;
+    }
+
+    if let Ok(config) = repo.config() {
+        if let Some(name) = remote_name {
+            if let Ok(proxy) = config.get_string(&format!("remote.{}.proxy", name)) {
+                return Some(proxy);
+            }
+        }
+        if let Ok(proxy) = config.get_string("http.proxy") {
+            return Some(proxy);
+        }
+    }
+
+    let env_names: &[&str] = if remote_url.scheme() == "https" {
+        &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+    } else {
+        &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+    };
+
+    env_names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// 检查主机是否匹配 `NO_PROXY` 环境变量
+///
+/// 支持 `*`、域名后缀（`example.com` 和 `.example.com` 都匹配其子域名）
+/// 以及带端口的条目（`example.com:8080`）。
+fn is_no_proxy(host: &str, port: Option<u16>) -> bool {
+    let Some(no_proxy) = ["NO_PROXY", "no_proxy"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok())
+    else {
+        return false;
+    };
+
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    no_proxy
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+
+            let (pattern, entry_port) = match entry.rsplit_once(':') {
+                Some((p, port)) if !p.contains(':') || p.ends_with(']') => {
+                    (p, port.parse::<u16>().ok())
+                }
+                _ => (entry, None),
+            };
+            if entry_port.is_some() && entry_port != port {
+                return false;
+            }
+
+            let pattern = pattern
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+                .trim_start_matches('[')
+                .trim_end_matches(']');
+
+            host.eq_ignore_ascii_case(pattern)
+                || host
+                    .to_ascii_lowercase()
+                    .ends_with(&format!(".{}", pattern.to_ascii_lowercase()))
+        })
+}
+
+/// 将代理地址转换为 libgit2 可用的 HTTP 代理地址
+///
+/// 没有协议前缀时按 http 处理；SOCKS5 代理通过本地的 HTTP 桥接转发，
+/// 同一代理的多次连接共用一个桥接。
+fn effective_proxy_url(proxy: &str) -> Result<String> {
+    let proxy = if proxy.contains("://") {
+        proxy.to_string()
+    } else {
+        format!("http://{}", proxy)
+    };
+
+    let url = Url::parse(&proxy).with_context(|| format!("无效的代理地址: {}", proxy))?;
+
+    match url.scheme() {
+        "http" | "https" => Ok(proxy),
+        "socks5" | "socks5h" => {
+            let addr = socks::bridge_addr(&url)
+                .with_context(|| format!("无法启动 SOCKS5 代理转发: {}", proxy))?;
+            Ok(format!("http://{}", addr))
+        }
+        scheme => bail!(
+            "不支持的代理协议: {}（支持 http、https、socks5、socks5h）",
+            scheme
+        ),
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{bail, Context, Result};
+use git2::{BranchType, Oid, Repository};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::branch::get_branch_info;
+use crate::models::{PruneCandidate, PruneReason};
+
+/// 解析基准分支，返回 (显示名称, 提交ID)
+///
+/// 未指定时使用远程 HEAD 指向的默认分支（优先 origin），
+/// 找不到时依次尝试本地的 main 和 master 分支。
+pub fn resolve_base(repo: &Repository, base: Option<&str>) -> Result<(String, Oid)> {
+    if let Some(base) = base {
+        let object = repo
+            .revparse_single(base)
+            .with_context(|| format!("找不到基准分支 {}", base))?;
+        let commit = object
+            .peel_to_commit()
+            .with_context(|| format!("基准分支 {} 没有指向提交", base))?;
+        return Ok((base.to_string(), commit.id()));
+    }
+
+    let mut remotes: Vec<String> = repo.remotes()?.iter().flatten().map(String::from).collect();
+    remotes.sort_by_key(|name| name != "origin");
+
+    for remote in &remotes {
+        let head_ref = format!("refs/remotes/{}/HEAD", remote);
+        if let Ok(reference) = repo.find_reference(&head_ref) {
+            if let Ok(resolved) = reference.resolve() {
+                if let (Some(name), Some(oid)) = (resolved.shorthand(), resolved.target()) {
+                    return Ok((name.to_string(), oid));
+                }
+            }
+        }
+    }
+
+    for name in ["main", "master"] {
+        if let Ok(branch) = repo.find_branch(name, BranchType::Local) {
+            if let Some(oid) = branch.get().target() {
+                return Ok((name.to_string(), oid));
+            }
+        }
+    }
+
+    bail!("无法确定基准分支，请使用 --base 指定")
+}
+
+/// 查找可以清理的本地分支
+///
+/// HEAD 分支和基准分支永远不会被列入。
+pub fn find_prune_candidates(
+    repo: &Repository,
+    base_name: &str,
+    base_oid: Oid,
+    stale_days: Option<u64>,
+) -> Result<Vec<PruneCandidate>> {
+    let (local_branches, _) = get_branch_info(repo)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    // 基准为远程跟踪分支时，对应的本地分支名（如 origin/main 对应 main）
+    let base_local = repo
+        .find_branch(base_name, BranchType::Remote)
+        .ok()
+        .and_then(|branch| branch.get().name().map(String::from))
+        .and_then(|refname| repo.branch_remote_name(&refname).ok())
+        .and_then(|remote| {
+            let prefix = format!("{}/", remote.as_str()?);
+            base_name.strip_prefix(&prefix).map(String::from)
+        });
+
+    let mut candidates = Vec::new();
+
+    for branch in local_branches {
+        // 跳过基准分支本身及其对应的本地分支，本地分支没有设置上游时按名称对应
+        if branch.is_head
+            || branch.name == base_name
+            || base_local.as_deref() == Some(branch.name.as_str())
+            || branch.upstream.as_deref() == Some(base_name)
+        {
+            continue;
+        }
+
+        let Some(oid) = repo
+            .find_branch(&branch.name, BranchType::Local)?
+            .get()
+            .target()
+        else {
+            continue;
+        };
+
+        let mut reasons = Vec::new();
+
+        if oid == base_oid || repo.graph_descendant_of(base_oid, oid)? {
+            reasons.push(PruneReason::Merged);
+        }
+
+        if branch.gone {
+            reasons.push(PruneReason::Gone);
+        }
+
+        if let (Some(days), Some(commit)) = (stale_days, branch.last_commit.as_ref()) {
+            let idle_days = ((now - commit.time).max(0) / 86400) as u64;
+            if idle_days >= days {
+                reasons.push(PruneReason::Stale(idle_days));
+            }
+        }
+
+        if !reasons.is_empty() {
+            candidates.push(PruneCandidate { branch, reasons });
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// 删除待清理的分支，返回已删除的分支
+pub fn delete_branches<'a>(
+    repo: &Repository,
+    candidates: &'a [PruneCandidate],
+) -> Result<Vec<&'a PruneCandidate>> {
+    let mut deleted = Vec::new();
+
+    for candidate in candidates {
+        let name = &candidate.branch.name;
+        let mut branch = repo
+            .find_branch(name, BranchType::Local)
+            .with_context(|| format!("找不到分支 {}", name))?;
+
+        // 再次确认不会删除当前分支
+        if branch.is_head() {
+            continue;
+        }
+
+        branch
+            .delete()
+            .with_context(|| format!("删除分支 {} 失败", name))?;
+        deleted.push(candidate);
+    }
+
+    Ok(deleted)
+}
@@ -0,0 +1,138 @@
+use anyhow::{bail, Context, Result};
+use git2::{Cred, CredentialType, Direction, PushOptions, RemoteCallbacks, Repository};
+use std::cell::RefCell;
+use std::path::Path;
+
+use super::proxy::proxy_options;
+
+/// 远程引用
+pub struct RemoteRef {
+    pub name: String,
+    pub oid: git2::Oid,
+}
+
+/// 创建带认证回调的 RemoteCallbacks
+///
+/// 依次尝试 SSH agent、git credential helper 和默认凭据。
+pub fn remote_callbacks(repo: &Repository) -> RemoteCallbacks<'_> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut tried_agent = false;
+    let mut tried_helper = false;
+
+    callbacks.credentials(move |url, username, allowed| {
+        if allowed.contains(CredentialType::SSH_KEY) && !tried_agent {
+            tried_agent = true;
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !tried_helper {
+            tried_helper = true;
+            if let Ok(config) = repo.config() {
+                return Cred::credential_helper(&config, url, username);
+            }
+        }
+
+        if allowed.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+
+        Err(git2::Error::from_str("没有可用的认证方式"))
+    });
+
+    callbacks
+}
+
+/// 连接远程仓库并列出所有引用（等同于 `git ls-remote`）
+pub fn list_remote_refs(
+    repo: &Repository,
+    remote_name: &str,
+    proxy: Option<&str>,
+) -> Result<Vec<RemoteRef>> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .with_context(|| format!("找不到远程仓库 {}", remote_name))?;
+    if remote.url().is_some_and(is_empty_local_repository) {
+        return Ok(Vec::new());
+    }
+    let proxy = proxy_options(repo, &remote, proxy)?;
+
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(remote_callbacks(repo)), Some(proxy))
+        .with_context(|| format!("无法连接远程仓库 {}", remote_name))?;
+
+    let refs = connection
+        .list()?
+        .iter()
+        .map(|head| RemoteRef {
+            name: head.name().to_string(),
+            oid: head.oid(),
+        })
+        .collect();
+
+    Ok(refs)
+}
+
+/// 远程仓库是否为没有任何引用的本地仓库
+///
+/// libgit2 的本地传输在没有引用时返回空指针作为引用列表，
+/// git2 的 `Remote::list` 会用它构造切片（未定义行为，调试构建下直接中止），
+/// 因此需要在连接前单独检查。其他传输的引用列表总是已分配的。
+fn is_empty_local_repository(url: &str) -> bool {
+    let path = url.strip_prefix("file://").unwrap_or(url);
+    if !Path::new(path).is_dir() {
+        return false;
+    }
+
+    // 无法解析的符号引用（如指向未创建分支的 HEAD）不会出现在引用列表中
+    Repository::open(path)
+        .and_then(|remote| {
+            let refs = remote.references()?;
+            Ok(refs.flatten().all(|reference| reference.resolve().is_err()))
+        })
+        .unwrap_or(false)
+}
+
+/// 推送引用到远程仓库
+///
+/// 远程拒绝任何一个引用时返回错误，并列出被拒绝的引用及原因。
+pub fn push_refspecs(
+    repo: &Repository,
+    remote_name: &str,
+    refspecs: &[String],
+    proxy: Option<&str>,
+) -> Result<()> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .with_context(|| format!("找不到远程仓库 {}", remote_name))?;
+
+    let rejected = RefCell::new(Vec::new());
+    let mut callbacks = remote_callbacks(repo);
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(message) = status {
+            rejected
+                .borrow_mut()
+                .push(format!("{} ({})", refname, message));
+        }
+        Ok(())
+    });
+
+    let mut options = PushOptions::new();
+    options.remote_callbacks(callbacks);
+    options.proxy_options(proxy_options(repo, &remote, proxy)?);
+
+    remote
+        .push(refspecs, Some(&mut options))
+        .with_context(|| format!("推送到远程仓库 {} 失败", remote_name))?;
+    drop(options);
+
+    let rejected = rejected.into_inner();
+    if !rejected.is_empty() {
+        bail!(
+            "远程仓库 {} 拒绝了以下引用: {}",
+            remote_name,
+            rejected.join(", ")
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,314 @@
+//! HTTP 到 SOCKS5 的本地桥接
+//!
+//! libgit2 只支持 HTTP 代理，为了支持 `socks5://` 代理，这里在本地启动一个
+//! 简单的 HTTP 代理，把 `CONNECT` 和普通 HTTP 请求通过 SOCKS5 转发出去。
+//! 同一个代理在进程内只启动一个桥接，命令结束时通过 [`shutdown_bridges`] 停止。
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+
+use url::Url;
+
+/// 请求头的最大长度
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+
+#[derive(Clone)]
+struct SocksServer {
+    addr: String,
+    username: Option<String>,
+    password: Option<String>,
+    /// socks5h: 由代理服务器解析域名
+    remote_dns: bool,
+}
+
+/// 已启动的桥接，按代理地址区分
+static BRIDGES: OnceLock<Mutex<HashMap<String, Bridge>>> = OnceLock::new();
+
+struct Bridge {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    listener: JoinHandle<()>,
+}
+
+/// 返回代理对应的本地 HTTP 代理监听地址，首次使用时启动桥接
+pub fn bridge_addr(proxy: &Url) -> io::Result<SocketAddr> {
+    let mut bridges = BRIDGES.get_or_init(Default::default).lock().unwrap();
+    if let Some(bridge) = bridges.get(proxy.as_str()) {
+        return Ok(bridge.addr);
+    }
+
+    let bridge = start_bridge(proxy)?;
+    let addr = bridge.addr;
+    bridges.insert(proxy.to_string(), bridge);
+    Ok(addr)
+}
+
+/// 停止所有桥接的监听线程
+pub fn shutdown_bridges() {
+    let Some(bridges) = BRIDGES.get() else {
+        return;
+    };
+
+    for (_, bridge) in bridges.lock().unwrap().drain() {
+        bridge.stop.store(true, Ordering::SeqCst);
+        // 连接一次监听地址，唤醒阻塞在 accept 上的线程
+        let _ = TcpStream::connect(bridge.addr);
+        let _ = bridge.listener.join();
+    }
+}
+
+fn start_bridge(proxy: &Url) -> io::Result<Bridge> {
+    let host = proxy
+        .host_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "代理地址缺少主机名"))?;
+    let server = SocksServer {
+        addr: format!("{}:{}", host, proxy.port().unwrap_or(1080)),
+        username: (!proxy.username().is_empty()).then(|| decode(proxy.username())),
+        password: proxy.password().map(decode),
+        remote_dns: proxy.scheme() == "socks5h",
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let listener = thread::spawn({
+        let stop = Arc::clone(&stop);
+        move || {
+            for client in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(client) = client else {
+                    continue;
+                };
+                let server = server.clone();
+                thread::spawn(move || {
+                    let _ = handle_client(client, &server);
+                });
+            }
+        }
+    });
+
+    Ok(Bridge {
+        addr,
+        stop,
+        listener,
+    })
+}
+
+/// 解码 URL 中百分号编码的用户名和密码
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn handle_client(mut client: TcpStream, server: &SocksServer) -> io::Result<()> {
+    let head = read_head(&mut client)?;
+    let head_text = String::from_utf8_lossy(&head);
+    let mut lines = head_text.split("\r\n");
+    let request_line = lines.next().unwrap_or("");
+    let mut parts = request_line.split_whitespace();
+    let (method, target, version) = (
+        parts.next().unwrap_or(""),
+        parts.next().unwrap_or(""),
+        parts.next().unwrap_or("HTTP/1.1"),
+    );
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let Some((host, port)) = split_host_port(target, 443) else {
+            return reply_error(&mut client);
+        };
+        let upstream = match connect(server, &host, port) {
+            Ok(stream) => stream,
+            Err(_) => return reply_error(&mut client),
+        };
+        client.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")?;
+        return pipe(client, upstream);
+    }
+
+    // 普通 HTTP 请求使用绝对地址，转换成源站可接受的相对地址后转发
+    let Ok(url) = Url::parse(target) else {
+        return reply_error(&mut client);
+    };
+    let host = url.host_str().unwrap_or("").to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let mut upstream = match connect(server, &host, port) {
+        Ok(stream) => stream,
+        Err(_) => return reply_error(&mut client),
+    };
+
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let mut request = format!("{} {} {}\r\n", method, path, version);
+    for line in lines {
+        let name = line.split(':').next().unwrap_or("").to_ascii_lowercase();
+        if name.starts_with("proxy-") {
+            continue;
+        }
+        request.push_str(line);
+        request.push_str("\r\n");
+    }
+    upstream.write_all(request.trim_end_matches("\r\n").as_bytes())?;
+    upstream.write_all(b"\r\n\r\n")?;
+
+    pipe(client, upstream)
+}
+
+/// 逐字节读取请求头，避免多读走请求体
+fn read_head(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+
+    while !head.ends_with(b"\r\n\r\n") {
+        if stream.read(&mut byte)? == 0 || head.len() > MAX_HEAD_SIZE {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        head.push(byte[0]);
+    }
+
+    head.truncate(head.len() - 4);
+    Ok(head)
+}
+
+fn split_host_port(target: &str, default_port: u16) -> Option<(String, u16)> {
+    match target.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => Some((
+            host.trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string(),
+            port.parse().ok()?,
+        )),
+        _ if !target.is_empty() => Some((target.to_string(), default_port)),
+        _ => None,
+    }
+}
+
+fn reply_error(client: &mut TcpStream) -> io::Result<()> {
+    client.write_all(b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n")
+}
+
+/// 通过 SOCKS5 服务器连接目标地址（RFC 1928 / RFC 1929）
+fn connect(server: &SocksServer, host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect(&server.addr)?;
+    let error = |msg: &str| io::Error::other(msg.to_string());
+
+    // 协商认证方式
+    if server.username.is_some() {
+        stream.write_all(&[5, 2, 0, 2])?;
+    } else {
+        stream.write_all(&[5, 1, 0])?;
+    }
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    match reply {
+        [5, 0] => {}
+        [5, 2] => {
+            let username = server.username.as_deref().unwrap_or("");
+            let password = server.password.as_deref().unwrap_or("");
+            if username.len() > 255 || password.len() > 255 {
+                return Err(error("SOCKS5 用户名或密码过长"));
+            }
+            let mut auth = vec![1, username.len() as u8];
+            auth.extend_from_slice(username.as_bytes());
+            auth.push(password.len() as u8);
+            auth.extend_from_slice(password.as_bytes());
+            stream.write_all(&auth)?;
+
+            stream.read_exact(&mut reply)?;
+            if reply[1] != 0 {
+                return Err(error("SOCKS5 认证失败"));
+            }
+        }
+        _ => return Err(error("SOCKS5 服务器不支持的认证方式")),
+    }
+
+    // 发送连接请求
+    let mut request = vec![5, 1, 0];
+    if server.remote_dns {
+        if host.len() > 255 {
+            return Err(error("主机名过长"));
+        }
+        request.push(3);
+        request.push(host.len() as u8);
+        request.extend_from_slice(host.as_bytes());
+    } else {
+        let addr = (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| error("无法解析主机名"))?;
+        match addr {
+            SocketAddr::V4(v4) => {
+                request.push(1);
+                request.extend_from_slice(&v4.ip().octets());
+            }
+            SocketAddr::V6(v6) => {
+                request.push(4);
+                request.extend_from_slice(&v6.ip().octets());
+            }
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    // 读取响应并跳过绑定地址
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header)?;
+    if header[1] != 0 {
+        return Err(error(&format!("SOCKS5 连接失败，错误码 {}", header[1])));
+    }
+    let addr_len = match header[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => return Err(error("SOCKS5 响应格式错误")),
+    };
+    let mut rest = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut rest)?;
+
+    Ok(stream)
+}
+
+/// 在客户端和上游之间双向转发数据
+fn pipe(client: TcpStream, upstream: TcpStream) -> io::Result<()> {
+    let mut client_reader = client.try_clone()?;
+    let mut upstream_writer = upstream.try_clone()?;
+
+    let forward = thread::spawn(move || {
+        let _ = io::copy(&mut client_reader, &mut upstream_writer);
+        let _ = upstream_writer.shutdown(Shutdown::Write);
+    });
+
+    let (mut upstream_reader, mut client_writer) = (upstream, client);
+    let _ = io::copy(&mut upstream_reader, &mut client_writer);
+    let _ = client_writer.shutdown(Shutdown::Write);
+
+    let _ = forward.join();
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use anyhow::{bail, Context, Result};
+use git2::{build::CheckoutBuilder, BranchType, Oid, Repository, Sort, StatusOptions};
+
+use crate::models::CommitInfo;
+
+/// 检查工作区是否有未提交的修改
+///
+/// 未跟踪的文件不影响切换分支，不计入其中。
+pub fn is_worktree_dirty(repo: &Repository) -> Result<bool> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options))?;
+    Ok(!statuses.is_empty())
+}
+
+/// 储藏未提交的修改，返回储藏的提交ID
+pub fn stash_changes(repo: &mut Repository) -> Result<Oid> {
+    let stasher = repo
+        .signature()
+        .context("无法获取提交者信息，请先配置 user.name 和 user.email")?;
+    repo.stash_save(&stasher, "giter switch: 切换分支前自动储藏", None)
+        .context("储藏未提交的修改失败")
+}
+
+/// 获取引用最近的提交，用于预览
+pub fn recent_commits(repo: &Repository, refname: &str, limit: usize) -> Result<Vec<CommitInfo>> {
+    let oid = repo.revparse_single(refname)?.peel_to_commit()?.id();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    revwalk.push(oid)?;
+
+    revwalk
+        .take(limit)
+        .map(|oid| Ok(CommitInfo::from_commit(&repo.find_commit(oid?)?)))
+        .collect()
+}
+
+/// 切换到分支，返回切换后的本地分支名
+///
+/// 远程分支（如 `origin/feature`）会切换到同名的本地分支，
+/// 本地分支不存在时创建并跟踪该远程分支。
+/// `force` 为 true 时丢弃工作区中与目标分支冲突的修改。
+pub fn checkout_branch(
+    repo: &Repository,
+    name: &str,
+    is_remote: bool,
+    force: bool,
+) -> Result<String> {
+    let local_name = if is_remote {
+        let remote_branch = repo
+            .find_branch(name, BranchType::Remote)
+            .with_context(|| format!("找不到远程分支 {}", name))?;
+        let (_, short_name) = name
+            .split_once('/')
+            .with_context(|| format!("无效的远程分支名 {}", name))?;
+
+        if repo.find_branch(short_name, BranchType::Local).is_err() {
+            let commit = remote_branch.get().peel_to_commit()?;
+            let mut branch = repo
+                .branch(short_name, &commit, false)
+                .with_context(|| format!("创建分支 {} 失败", short_name))?;
+            branch
+                .set_upstream(Some(name))
+                .with_context(|| format!("设置 {} 的上游分支失败", short_name))?;
+        }
+        short_name.to_string()
+    } else {
+        name.to_string()
+    };
+
+    let branch = repo
+        .find_branch(&local_name, BranchType::Local)
+        .with_context(|| format!("找不到分支 {}", local_name))?;
+    let refname = branch
+        .get()
+        .name()
+        .context("分支名不是有效的 UTF-8")?
+        .to_string();
+    let tree = branch.get().peel_to_tree()?;
+
+    let mut checkout = CheckoutBuilder::new();
+    if force {
+        checkout.force();
+    } else {
+        checkout.safe();
+    }
+
+    if let Err(e) = repo.checkout_tree(tree.as_object(), Some(&mut checkout)) {
+        bail!(
+            "切换到分支 {} 失败: {}（可以使用 --stash 储藏修改或 --force 丢弃修改）",
+            local_name,
+            e.message()
+        );
+    }
+    repo.set_head(&refname)
+        .with_context(|| format!("切换到分支 {} 失败", local_name))?;
+
+    Ok(local_name)
+}
@@ -1,9 +1,43 @@
-use anyhow::Result;
-use git2::{ObjectType, Repository};
+use anyhow::{bail, Context, Result};
+use colored::*;
+use git2::{ErrorCode, ObjectType, Oid, Reference, Repository};
+use std::collections::HashMap;
 
-use crate::models::TagInfo;
+use super::remote::{list_remote_refs, push_refspecs};
+use crate::cli::BumpLevel;
+use crate::models::{compare_tag_names, TagInfo, TagStatus, TagVersion};
 
-pub fn get_tag_info(repo: &Repository) -> Result<(Vec<TagInfo>, Vec<TagInfo>)> {
+/// 获取本地和远程标签并标记同步状态
+///
+/// `check_remote` 为 false 时不连接远程仓库，只返回本地标签，状态为未检查。
+pub fn get_tag_info(
+    repo: &Repository,
+    proxy: Option<&str>,
+    check_remote: bool,
+) -> Result<(Vec<TagInfo>, Vec<TagInfo>)> {
+    let mut tags = get_local_tags(repo)?;
+    if !check_remote {
+        for tag in &mut tags {
+            tag.status = TagStatus::Unchecked;
+        }
+        return Ok((tags, Vec::new()));
+    }
+
+    let mut remote_tags = Vec::new();
+
+    for remote_name in repo.remotes()?.iter().flatten() {
+        match get_remote_tags(repo, remote_name, proxy) {
+            Ok(mut list) => remote_tags.append(&mut list),
+            Err(e) => eprintln!("{}", format!("警告: {:#}", e).yellow()),
+        }
+    }
+
+    mark_status(&mut tags, &mut remote_tags);
+
+    Ok((tags, remote_tags))
+}
+
+pub(super) fn get_local_tags(repo: &Repository) -> Result<Vec<TagInfo>> {
     let mut tags = Vec::new();
 
     // 使用 tag_foreach 遍历所有标签
@@ -35,9 +69,270 @@ pub fn get_tag_info(repo: &Repository) -> Result<(Vec<TagInfo>, Vec<TagInfo>)> {
         true
     })?;
 
-    // 按名称排序
-    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    // 按语义化版本排序
+    tags.sort_by(|a, b| compare_tag_names(&a.name, &b.name));
+
+    Ok(tags)
+}
+
+fn get_remote_tags(
+    repo: &Repository,
+    remote_name: &str,
+    proxy: Option<&str>,
+) -> Result<Vec<TagInfo>> {
+    // 带注释的标签会同时返回 `refs/tags/x` 和 `refs/tags/x^{}`，
+    // 后者是解析后的提交，优先使用
+    let mut commits: HashMap<String, git2::Oid> = HashMap::new();
+
+    for remote_ref in list_remote_refs(repo, remote_name, proxy)? {
+        let Some(name) = remote_ref.name.strip_prefix("refs/tags/") else {
+            continue;
+        };
+
+        match name.strip_suffix("^{}") {
+            Some(name) => {
+                commits.insert(name.to_string(), remote_ref.oid);
+            }
+            None => {
+                commits.entry(name.to_string()).or_insert(remote_ref.oid);
+            }
+        }
+    }
+
+    let mut tags: Vec<TagInfo> = commits
+        .into_iter()
+        .map(|(name, oid)| TagInfo::remote(name, oid.to_string(), remote_name.to_string()))
+        .collect();
+
+    tags.sort_by(|a, b| compare_tag_names(&a.name, &b.name));
+
+    Ok(tags)
+}
+
+/// 对比本地和远程标签，标记同步状态
+fn mark_status(local: &mut [TagInfo], remote: &mut [TagInfo]) {
+    let local_commits: HashMap<&str, &str> = local
+        .iter()
+        .map(|t| (t.name.as_str(), t.commit.as_str()))
+        .collect();
+
+    let mut remote_commits: HashMap<&str, Vec<&str>> = HashMap::new();
+    for tag in remote.iter() {
+        remote_commits
+            .entry(tag.name.as_str())
+            .or_default()
+            .push(tag.commit.as_str());
+    }
+
+    let local_status: Vec<TagStatus> = local
+        .iter()
+        .map(|tag| match remote_commits.get(tag.name.as_str()) {
+            None => TagStatus::LocalOnly,
+            Some(commits) if commits.contains(&tag.commit.as_str()) => TagStatus::Synced,
+            Some(_) => TagStatus::Diverged,
+        })
+        .collect();
+
+    let remote_status: Vec<TagStatus> = remote
+        .iter()
+        .map(|tag| match local_commits.get(tag.name.as_str()) {
+            None => TagStatus::RemoteOnly,
+            Some(commit) if *commit == tag.commit => TagStatus::Synced,
+            Some(_) => TagStatus::Diverged,
+        })
+        .collect();
+
+    for (tag, status) in local.iter_mut().zip(local_status) {
+        tag.status = status;
+    }
+    for (tag, status) in remote.iter_mut().zip(remote_status) {
+        tag.status = status;
+    }
+}
+
+/// 创建标签，提供 message 时创建带注释的标签，否则创建轻量标签
+///
+/// 返回标签指向的提交ID。
+pub fn create_tag(
+    repo: &Repository,
+    name: &str,
+    message: Option<&str>,
+    commit: Option<&str>,
+    force: bool,
+) -> Result<Oid> {
+    if !Reference::is_valid_name(&format!("refs/tags/{}", name)) {
+        bail!("无效的标签名: {}", name);
+    }
+
+    if !force && repo.find_reference(&format!("refs/tags/{}", name)).is_ok() {
+        bail!("标签 {} 已存在，使用 --force 覆盖", name);
+    }
+
+    let target = commit.unwrap_or("HEAD");
+    let object = repo
+        .revparse_single(target)
+        .with_context(|| format!("找不到提交 {}", target))?
+        .peel(ObjectType::Commit)
+        .with_context(|| format!("{} 不是一个提交", target))?;
+
+    match message {
+        Some(message) => {
+            let tagger = repo
+                .signature()
+                .context("无法获取提交者信息，请先配置 user.name 和 user.email")?;
+            repo.tag(name, &object, &tagger, message, force)
+        }
+        None => repo.tag_lightweight(name, &object, force),
+    }
+    .with_context(|| format!("创建标签 {} 失败", name))?;
+
+    Ok(object.id())
+}
+
+/// 删除本地标签
+pub fn delete_tag(repo: &Repository, name: &str) -> Result<()> {
+    repo.tag_delete(name).map_err(|e| {
+        if e.code() == ErrorCode::NotFound {
+            anyhow::anyhow!("标签 {} 不存在", name)
+        } else {
+            anyhow::Error::new(e).context(format!("删除标签 {} 失败", name))
+        }
+    })
+}
+
+/// 删除远程标签
+pub fn delete_remote_tag(
+    repo: &Repository,
+    remote_name: &str,
+    name: &str,
+    proxy: Option<&str>,
+) -> Result<()> {
+    push_refspecs(repo, remote_name, &[format!(":refs/tags/{}", name)], proxy)
+}
+
+/// 推送标签到远程仓库，name 为 None 时推送所有本地标签
+///
+/// 返回推送的标签名列表。
+pub fn push_tags(
+    repo: &Repository,
+    remote_name: &str,
+    name: Option<&str>,
+    force: bool,
+    proxy: Option<&str>,
+) -> Result<Vec<String>> {
+    let names: Vec<String> = match name {
+        Some(name) => {
+            if repo.find_reference(&format!("refs/tags/{}", name)).is_err() {
+                bail!("标签 {} 不存在", name);
+            }
+            vec![name.to_string()]
+        }
+        None => repo
+            .tag_names(None)?
+            .iter()
+            .flatten()
+            .map(String::from)
+            .collect(),
+    };
+
+    // 跳过远程已是最新的标签；远程同名标签指向其他对象时需要 --force
+    let remote_tags: HashMap<String, Oid> = list_remote_refs(repo, remote_name, proxy)?
+        .into_iter()
+        .filter_map(|r| {
+            r.name
+                .strip_prefix("refs/tags/")
+                .filter(|name| !name.ends_with("^{}"))
+                .map(|name| (name.to_string(), r.oid))
+        })
+        .collect();
+
+    let mut conflicts = Vec::new();
+    let mut names_to_push = Vec::new();
+    for name in names {
+        let local_oid = repo.refname_to_id(&format!("refs/tags/{}", name))?;
+        match remote_tags.get(&name) {
+            Some(remote_oid) if *remote_oid == local_oid => {}
+            Some(_) if !force => conflicts.push(name),
+            _ => names_to_push.push(name),
+        }
+    }
+
+    if !conflicts.is_empty() {
+        bail!(
+            "远程仓库 {} 中以下标签已存在且指向不同的提交，使用 --force 覆盖: {}",
+            remote_name,
+            conflicts.join(", ")
+        );
+    }
+
+    let names = names_to_push;
+    if names.is_empty() {
+        return Ok(names);
+    }
+
+    let prefix = if force { "+" } else { "" };
+    let refspecs: Vec<String> = names
+        .iter()
+        .map(|name| format!("{}refs/tags/{}:refs/tags/{}", prefix, name, name))
+        .collect();
+
+    push_refspecs(repo, remote_name, &refspecs, proxy)?;
+
+    Ok(names)
+}
+
+/// 查找最新的本地版本标签
+///
+/// 未指定前缀且仓库中存在多种前缀时返回错误，避免混淆不同项目的版本。
+pub fn find_latest_version(
+    repo: &Repository,
+    prefix: Option<&str>,
+    include_pre: bool,
+) -> Result<Option<TagVersion>> {
+    let versions: Vec<TagVersion> = repo
+        .tag_names(None)?
+        .iter()
+        .flatten()
+        .filter_map(TagVersion::parse)
+        .filter(|v| prefix.is_none_or(|p| v.prefix == p))
+        .collect();
+
+    if prefix.is_none() {
+        let mut prefixes: Vec<&str> = versions.iter().map(|v| v.prefix.as_str()).collect();
+        prefixes.sort_unstable();
+        prefixes.dedup();
+        if prefixes.len() > 1 {
+            bail!(
+                "仓库中存在多种版本前缀，请使用 --prefix 指定: {}",
+                prefixes
+                    .iter()
+                    .map(|p| format!("\"{}\"", p))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    Ok(versions
+        .into_iter()
+        .filter(|v| include_pre || v.version.pre.is_empty())
+        .max_by(|a, b| a.version.cmp(&b.version)))
+}
+
+/// 计算下一个版本标签
+///
+/// 基于最新的版本标签（包含预发布版本）递增，没有版本标签时从 0.0.0 开始，
+/// 前缀默认为 `v`。
+pub fn next_tag_version(
+    repo: &Repository,
+    prefix: Option<&str>,
+    level: BumpLevel,
+    preid: Option<&str>,
+) -> Result<TagVersion> {
+    let current = find_latest_version(repo, prefix, true)?
+        .unwrap_or_else(|| TagVersion::new(prefix.unwrap_or("v"), semver::Version::new(0, 0, 0)));
 
-    // 为了保持接口兼容，返回所有标签作为本地标签
-    Ok((tags, Vec::new()))
+    current
+        .bump(level, preid)
+        .with_context(|| format!("无效的预发布标识: {}", preid.unwrap_or("")))
 }
@@ -0,0 +1,22 @@
+use crate::models::{format_date, Changelog};
+
+/// 以 Markdown 格式输出变更日志
+pub fn display_changelog(changelog: &Changelog) {
+    let title = changelog.version.as_deref().unwrap_or("未发布");
+    println!("## {} ({})", title, format_date(changelog.time));
+
+    if changelog.is_empty() {
+        println!();
+        println!("没有新的提交");
+        return;
+    }
+
+    for section in &changelog.sections {
+        println!();
+        println!("### {}", section.title);
+        println!();
+        for entry in &section.entries {
+            println!("{}", entry.display_markdown());
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! 机器可读的输出格式
+//!
+//! 与表格和列表不同，这里的输出只使用模型中的原始字段，不包含任何颜色代码，
+//! 便于脚本和 CI 解析。
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::cli::OutputFormat;
+use crate::models::{BranchInfo, Changelog, CommitInfo, PruneCandidate, TagInfo};
+
+/// JSON/YAML 输出的顶层结构，隐藏的分组不会输出
+#[derive(Serialize)]
+struct Listing<'a, T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    local: Option<&'a [T]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote: Option<&'a [T]>,
+}
+
+pub fn display_branches(
+    local: &[BranchInfo],
+    remote: &[BranchInfo],
+    show_local: bool,
+    show_remote: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let listing = Listing {
+        local: show_local.then_some(local),
+        remote: show_remote.then_some(remote),
+    };
+
+    let mut rows = Vec::new();
+    for (kind, branches) in [("local", listing.local), ("remote", listing.remote)] {
+        for branch in branches.unwrap_or_default() {
+            let mut row = vec![
+                kind.to_string(),
+                branch.name.clone(),
+                branch.is_head.to_string(),
+                branch.upstream.clone().unwrap_or_default(),
+                branch.ahead.to_string(),
+                branch.behind.to_string(),
+                branch.gone.to_string(),
+            ];
+            row.extend(commit_fields(branch.last_commit.as_ref()));
+            rows.push(row);
+        }
+    }
+
+    let headers = [
+        "type", "name", "is_head", "upstream", "ahead", "behind", "gone", "commit", "author",
+        "time", "summary",
+    ];
+
+    print_output(&listing, &headers, &rows, format)
+}
+
+pub fn display_tags(
+    local: &[TagInfo],
+    remote: &[TagInfo],
+    show_local: bool,
+    show_remote: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let listing = Listing {
+        local: show_local.then_some(local),
+        remote: show_remote.then_some(remote),
+    };
+
+    let mut rows = Vec::new();
+    for (kind, tags) in [("local", listing.local), ("remote", listing.remote)] {
+        for tag in tags.unwrap_or_default() {
+            rows.push(vec![
+                kind.to_string(),
+                tag.remote.clone().unwrap_or_default(),
+                tag.name.clone(),
+                tag.commit.clone(),
+                tag.status.code().to_string(),
+                tag.message.as_deref().unwrap_or("").trim_end().to_string(),
+            ]);
+        }
+    }
+
+    let headers = ["type", "remote", "name", "commit", "status", "message"];
+
+    print_output(&listing, &headers, &rows, format)
+}
+
+pub fn display_prune_plan(candidates: &[PruneCandidate], format: OutputFormat) -> Result<()> {
+    let rows: Vec<Vec<String>> = candidates
+        .iter()
+        .map(|candidate| {
+            let reasons: Vec<String> = candidate.reasons.iter().map(|r| r.code()).collect();
+            let mut row = vec![
+                candidate.branch.name.clone(),
+                reasons.join(";"),
+                candidate.branch.upstream.clone().unwrap_or_default(),
+            ];
+            row.extend(commit_fields(candidate.branch.last_commit.as_ref()));
+            row
+        })
+        .collect();
+
+    let headers = [
+        "name", "reasons", "upstream", "commit", "author", "time", "summary",
+    ];
+
+    print_output(&candidates, &headers, &rows, format)
+}
+
+pub fn display_changelog(changelog: &Changelog, format: OutputFormat) -> Result<()> {
+    let rows: Vec<Vec<String>> = changelog
+        .entries()
+        .map(|(section, entry)| {
+            let mut row = vec![
+                section.kind.to_string(),
+                entry.kind.clone().unwrap_or_default(),
+                entry.scope.clone().unwrap_or_default(),
+                entry.breaking.to_string(),
+                entry.description.clone(),
+            ];
+            row.extend(commit_fields(Some(&entry.commit)));
+            row
+        })
+        .collect();
+
+    let headers = [
+        "section",
+        "type",
+        "scope",
+        "breaking",
+        "description",
+        "commit",
+        "author",
+        "time",
+        "summary",
+    ];
+
+    print_output(changelog, &headers, &rows, format)
+}
+
+fn commit_fields(commit: Option<&CommitInfo>) -> Vec<String> {
+    match commit {
+        Some(commit) => vec![
+            commit.id.clone(),
+            commit.author.clone(),
+            commit.time.to_string(),
+            commit.summary.clone(),
+        ],
+        None => vec![String::new(); 4],
+    }
+}
+
+fn print_output<T: Serialize>(
+    value: &T,
+    headers: &[&str],
+    rows: &[Vec<String>],
+    format: OutputFormat,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value)?),
+        OutputFormat::Csv => print_delimited(headers, rows, ','),
+        OutputFormat::Tsv => print_delimited(headers, rows, '\t'),
+    }
+    Ok(())
+}
+
+fn print_delimited(headers: &[&str], rows: &[Vec<String>], delimiter: char) {
+    let escape = |field: &str| -> String {
+        if delimiter == '\t' {
+            // TSV 不支持引号，转义制表符和换行
+            field
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        } else if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    };
+
+    let separator = delimiter.to_string();
+    println!("{}", headers.join(&separator));
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| escape(f)).collect();
+        println!("{}", fields.join(&separator));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::{BranchInfo, TagInfo};
+use crate::models::{BranchInfo, PruneCandidate, TagInfo};
 
 pub fn display_branches(
     local: &[BranchInfo],
@@ -9,11 +9,17 @@ pub fn display_branches(
     if show_local {
         println!("本地分支:");
         for branch in local {
+            let mut line = branch.display_name();
             if let Some(ref upstream) = branch.upstream {
-                println!("{} -> {}", branch.display_name(), upstream);
-            } else {
-                println!("{}", branch.display_name());
+                line = format!("{} -> {}", line, upstream);
+            }
+            if let Some(tracking) = branch.display_tracking() {
+                line = format!("{} {}", line, tracking);
             }
+            if let Some(last_commit) = branch.display_last_commit() {
+                line = format!("{}  {}", line, last_commit);
+            }
+            println!("{}", line);
         }
     }
 
@@ -24,7 +30,10 @@ pub fn display_branches(
     if show_remote {
         println!("远程分支:");
         for branch in remote {
-            println!("  {}", branch.name);
+            match branch.display_last_commit() {
+                Some(last_commit) => println!("  {}  {}", branch.name, last_commit),
+                None => println!("  {}", branch.name),
+            }
         }
     }
 }
@@ -35,13 +44,19 @@ pub fn display_tags(local: &[TagInfo], remote: &[TagInfo], show_local: bool, sho
         for tag in local {
             if let Some(ref message) = tag.message {
                 println!(
-                    "  {} {} {}",
+                    "  {} {} {} [{}]",
                     tag.display_name(true),
                     tag.display_commit(),
-                    message
+                    message,
+                    tag.display_status()
                 );
             } else {
-                println!("  {} {}", tag.display_name(true), tag.display_commit());
+                println!(
+                    "  {} {} [{}]",
+                    tag.display_name(true),
+                    tag.display_commit(),
+                    tag.display_status()
+                );
             }
         }
     }
@@ -55,14 +70,39 @@ pub fn display_tags(local: &[TagInfo], remote: &[TagInfo], show_local: bool, sho
         for tag in remote {
             if let Some(ref message) = tag.message {
                 println!(
-                    "  {} {} {}",
+                    "  {} {} {} [{}]",
                     tag.display_name(false),
                     tag.display_commit(),
-                    message
+                    message,
+                    tag.display_status()
                 );
             } else {
-                println!("  {} {}", tag.display_name(false), tag.display_commit());
+                println!(
+                    "  {} {} [{}]",
+                    tag.display_name(false),
+                    tag.display_commit(),
+                    tag.display_status()
+                );
             }
         }
     }
 }
+
+pub fn display_prune_plan(candidates: &[PruneCandidate]) {
+    println!("待清理分支:");
+    for candidate in candidates {
+        match candidate.branch.display_last_commit() {
+            Some(last_commit) => println!(
+                "  {} [{}]  {}",
+                candidate.branch.name,
+                candidate.display_reasons(),
+                last_commit
+            ),
+            None => println!(
+                "  {} [{}]",
+                candidate.branch.name,
+                candidate.display_reasons()
+            ),
+        }
+    }
+}
@@ -1,5 +1,16 @@
+mod changelog;
+pub mod format;
 mod list;
+mod picker;
 mod table;
 
-pub use list::{display_branches as display_branches_list, display_tags as display_tags_list};
-pub use table::{display_branches as display_branches_table, display_tags as display_tags_table};
+pub use changelog::display_changelog as display_changelog_markdown;
+pub use list::{
+    display_branches as display_branches_list, display_prune_plan as display_prune_plan_list,
+    display_tags as display_tags_list,
+};
+pub use picker::pick_branch;
+pub use table::{
+    display_branches as display_branches_table, display_prune_plan as display_prune_plan_table,
+    display_tags as display_tags_table,
+};
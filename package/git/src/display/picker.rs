@@ -0,0 +1,305 @@
+//! 交互式分支选择界面
+//!
+//! 上方为输入框，输入内容对分支名进行模糊匹配；左侧为分支列表，
+//! 右侧预览当前选中分支的最近提交。
+
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+
+use anyhow::{bail, Result};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::models::{BranchInfo, CommitInfo};
+
+struct Item<'a> {
+    branch: &'a BranchInfo,
+    is_remote: bool,
+}
+
+struct Picker<'a> {
+    items: Vec<Item<'a>>,
+    query: String,
+    /// 匹配的分支索引及匹配到的字符位置
+    matches: Vec<(usize, Vec<usize>)>,
+    state: ListState,
+    matcher: SkimMatcherV2,
+}
+
+impl<'a> Picker<'a> {
+    fn new(local: &'a [BranchInfo], remote: &'a [BranchInfo], query: String) -> Self {
+        let items = local
+            .iter()
+            .map(|branch| Item {
+                branch,
+                is_remote: false,
+            })
+            .chain(
+                remote
+                    .iter()
+                    .filter(|branch| !branch.name.ends_with("/HEAD"))
+                    .map(|branch| Item {
+                        branch,
+                        is_remote: true,
+                    }),
+            )
+            .collect();
+
+        let mut picker = Self {
+            items,
+            query,
+            matches: Vec::new(),
+            state: ListState::default(),
+            matcher: SkimMatcherV2::default().ignore_case(),
+        };
+        picker.update_matches();
+        picker
+    }
+
+    /// 根据输入重新匹配，有输入时按匹配得分排序
+    fn update_matches(&mut self) {
+        if self.query.is_empty() {
+            self.matches = (0..self.items.len()).map(|i| (i, Vec::new())).collect();
+        } else {
+            let mut scored: Vec<(i64, usize, Vec<usize>)> = self
+                .items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    self.matcher
+                        .fuzzy_indices(&item.branch.name, &self.query)
+                        .map(|(score, indices)| (score, i, indices))
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            self.matches = scored.into_iter().map(|(_, i, idx)| (i, idx)).collect();
+        }
+
+        self.state.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn selected(&self) -> Option<&Item<'a>> {
+        self.state
+            .selected()
+            .and_then(|i| self.matches.get(i))
+            .map(|(i, _)| &self.items[*i])
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() as isize - 1;
+        let current = self.state.selected().unwrap_or(0) as isize;
+        self.state
+            .select(Some((current + delta).clamp(0, last) as usize));
+    }
+
+    fn render(&mut self, frame: &mut Frame, preview: &[CommitInfo]) {
+        let [input_area, main_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(main_area);
+
+        // 输入框
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(self.query.as_str()),
+        ]))
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            " 切换分支 {}/{} ",
+            self.matches.len(),
+            self.items.len()
+        )));
+        frame.render_widget(input, input_area);
+        frame.set_cursor_position((
+            input_area.x + 3 + self.query.chars().count() as u16,
+            input_area.y + 1,
+        ));
+
+        // 分支列表
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|(i, indices)| ListItem::new(item_line(&self.items[*i], indices)))
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" 分支 "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+
+        self.render_preview(frame, preview_area, preview);
+
+        let help = Paragraph::new("↑/↓ 选择  Enter 切换  Ctrl-U 清空  Esc 退出")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, help_area);
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect, preview: &[CommitInfo]) {
+        let mut lines = Vec::new();
+
+        if let Some(item) = self.selected() {
+            let branch = item.branch;
+            if let Some(ref upstream) = branch.upstream {
+                let mut tracking = format!("上游: {}", upstream);
+                if branch.gone {
+                    tracking.push_str(" (已删除)");
+                } else if branch.ahead > 0 || branch.behind > 0 {
+                    tracking.push_str(&format!(" [领先 {}, 落后 {}]", branch.ahead, branch.behind));
+                }
+                lines.push(Line::from(tracking));
+            }
+            if item.is_remote {
+                lines.push(Line::styled(
+                    "远程分支，切换时会创建同名的本地跟踪分支",
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+
+            for commit in preview {
+                lines.push(Line::from(vec![
+                    Span::styled(commit.short_id.clone(), Style::default().fg(Color::Yellow)),
+                    Span::raw(" "),
+                    Span::raw(commit.summary.clone()),
+                ]));
+                lines.push(Line::styled(
+                    format!("        {} · {}", commit.author, commit.display_time()),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(" 最近提交 "));
+        frame.render_widget(paragraph, area);
+    }
+}
+
+/// 分支列表中的一行，高亮模糊匹配到的字符
+fn item_line<'a>(item: &Item, indices: &[usize]) -> Line<'a> {
+    let branch = item.branch;
+    let base = if branch.is_head {
+        Style::default().fg(Color::Green)
+    } else if item.is_remote {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    let highlight = base
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+    let mut spans = vec![Span::styled(if branch.is_head { "* " } else { "  " }, base)];
+    spans.extend(branch.name.chars().enumerate().map(|(i, c)| {
+        let style = if indices.contains(&i) {
+            highlight
+        } else {
+            base
+        };
+        Span::styled(c.to_string(), style)
+    }));
+    if let Some(ref commit) = branch.last_commit {
+        spans.push(Span::styled(
+            format!("  {}", commit.display_time()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    Line::from(spans)
+}
+
+/// 打开分支选择界面，返回选中的分支名以及是否为远程分支
+///
+/// `load_preview` 用于获取分支最近的提交，结果会被缓存。
+/// 按 Esc 或 Ctrl-C 退出时返回 None。
+pub fn pick_branch<F>(
+    local: &[BranchInfo],
+    remote: &[BranchInfo],
+    query: String,
+    load_preview: F,
+) -> Result<Option<(String, bool)>>
+where
+    F: FnMut(&BranchInfo, bool) -> Vec<CommitInfo>,
+{
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        bail!("giter switch 需要在终端中运行");
+    }
+
+    let mut picker = Picker::new(local, remote, query);
+    let mut terminal = ratatui::try_init()?;
+    let result = run(&mut terminal, &mut picker, load_preview);
+    ratatui::restore();
+    result
+}
+
+fn run<F>(
+    terminal: &mut DefaultTerminal,
+    picker: &mut Picker,
+    mut load_preview: F,
+) -> Result<Option<(String, bool)>>
+where
+    F: FnMut(&BranchInfo, bool) -> Vec<CommitInfo>,
+{
+    let mut previews: HashMap<(bool, String), Vec<CommitInfo>> = HashMap::new();
+
+    loop {
+        let preview = match picker.selected() {
+            Some(item) => previews
+                .entry((item.is_remote, item.branch.name.clone()))
+                .or_insert_with(|| load_preview(item.branch, item.is_remote))
+                .clone(),
+            None => Vec::new(),
+        };
+        terminal.draw(|frame| picker.render(frame, &preview))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if ctrl => return Ok(None),
+            KeyCode::Enter => {
+                if let Some(item) = picker.selected() {
+                    return Ok(Some((item.branch.name.clone(), item.is_remote)));
+                }
+            }
+            KeyCode::Up => picker.move_by(-1),
+            KeyCode::Down => picker.move_by(1),
+            KeyCode::Char('p') if ctrl => picker.move_by(-1),
+            KeyCode::Char('n') if ctrl => picker.move_by(1),
+            KeyCode::PageUp => picker.move_by(-10),
+            KeyCode::PageDown => picker.move_by(10),
+            KeyCode::Char('u') if ctrl => {
+                picker.query.clear();
+                picker.update_matches();
+            }
+            KeyCode::Backspace if picker.query.pop().is_some() => picker.update_matches(),
+            KeyCode::Char(c) if !ctrl => {
+                picker.query.push(c);
+                picker.update_matches();
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::{BranchInfo, TagInfo};
+use crate::models::{BranchInfo, PruneCandidate, TagInfo};
 use prettytable::{Cell, Row, Table};
 
 pub fn display_branches(
@@ -12,6 +12,12 @@ pub fn display_branches(
         Cell::new("类型").style_spec("Fb"),
         Cell::new("分支名").style_spec("Fb"),
         Cell::new("上游分支").style_spec("Fb"),
+        Cell::new("领先").style_spec("Fb"),
+        Cell::new("落后").style_spec("Fb"),
+        Cell::new("最后提交").style_spec("Fb"),
+        Cell::new("作者").style_spec("Fb"),
+        Cell::new("时间").style_spec("Fb"),
+        Cell::new("提交信息").style_spec("Fb"),
     ]));
 
     // 添加本地分支
@@ -21,6 +27,12 @@ pub fn display_branches(
                 Cell::new("本地"),
                 Cell::new(&branch.display_name()),
                 Cell::new(&branch.display_upstream()),
+                Cell::new(&branch.display_ahead()),
+                Cell::new(&branch.display_behind()),
+                Cell::new(&branch.display_commit_id()),
+                Cell::new(&branch.display_author()),
+                Cell::new(&branch.display_time()),
+                Cell::new(&branch.display_summary()),
             ]));
         }
     }
@@ -32,6 +44,12 @@ pub fn display_branches(
                 Cell::new("远程"),
                 Cell::new(&branch.name),
                 Cell::new("-"),
+                Cell::new("-"),
+                Cell::new("-"),
+                Cell::new(&branch.display_commit_id()),
+                Cell::new(&branch.display_author()),
+                Cell::new(&branch.display_time()),
+                Cell::new(&branch.display_summary()),
             ]));
         }
     }
@@ -46,6 +64,7 @@ pub fn display_tags(local: &[TagInfo], remote: &[TagInfo], show_local: bool, sho
         Cell::new("标签名").style_spec("Fb"),
         Cell::new("提交ID").style_spec("Fb"),
         Cell::new("消息").style_spec("Fb"),
+        Cell::new("状态").style_spec("Fb"),
     ]));
 
     if show_local {
@@ -55,6 +74,7 @@ pub fn display_tags(local: &[TagInfo], remote: &[TagInfo], show_local: bool, sho
                 Cell::new(&tag.display_name(true)),
                 Cell::new(&tag.display_commit()),
                 Cell::new(&tag.display_message()),
+                Cell::new(&tag.display_status()),
             ]));
         }
     }
@@ -66,9 +86,36 @@ pub fn display_tags(local: &[TagInfo], remote: &[TagInfo], show_local: bool, sho
                 Cell::new(&tag.display_name(false)),
                 Cell::new(&tag.display_commit()),
                 Cell::new(&tag.display_message()),
+                Cell::new(&tag.display_status()),
             ]));
         }
     }
 
     table.printstd();
 }
+
+pub fn display_prune_plan(candidates: &[PruneCandidate]) {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("分支名").style_spec("Fb"),
+        Cell::new("原因").style_spec("Fb"),
+        Cell::new("上游分支").style_spec("Fb"),
+        Cell::new("最后提交").style_spec("Fb"),
+        Cell::new("时间").style_spec("Fb"),
+        Cell::new("提交信息").style_spec("Fb"),
+    ]));
+
+    for candidate in candidates {
+        let branch = &candidate.branch;
+        table.add_row(Row::new(vec![
+            Cell::new(&branch.name),
+            Cell::new(&candidate.display_reasons()),
+            Cell::new(&branch.display_upstream()),
+            Cell::new(&branch.display_commit_id()),
+            Cell::new(&branch.display_time()),
+            Cell::new(&branch.display_summary()),
+        ]));
+    }
+
+    table.printstd();
+}
@@ -7,33 +7,99 @@ mod commands;
 mod display;
 mod models;
 
-use cli::{Cli, Commands};
+use cli::{BranchCommands, Cli, Commands, TagCommands};
 
 fn main() -> Result<()> {
-    let mut cli = Cli::parse();
+    let result = run();
+    // 停止为 SOCKS5 代理启动的本地桥接
+    commands::shutdown_bridges();
+    result
+}
 
-    // 设置代理
-    if let Some(proxy) = cli.proxy.take() {
-        std::env::set_var("http.proxy", proxy);
-    }
+fn run() -> Result<()> {
+    let cli = Cli::parse();
 
     // 打开仓库
-    let repo = Repository::discover(&cli.get_repo_path())
+    let repo = Repository::discover(cli.get_repo_path())
         .with_context(|| format!("无法在 {} 中找到 Git 仓库", cli.get_repo_path().display()))?;
 
     // 处理子命令
     match cli.command {
         Commands::Branch {
+            command:
+                Some(BranchCommands::Prune {
+                    base,
+                    stale_days,
+                    dry_run: _,
+                    yes,
+                    list,
+                }),
+            ..
+        } => {
+            let (base_name, base_oid) = commands::resolve_base(&repo, base.as_deref())?;
+            let candidates =
+                commands::find_prune_candidates(&repo, &base_name, base_oid, stale_days)?;
+
+            // 使用机器可读格式时，提示信息输出到 stderr，避免混入结果
+            let notice = |message: String| {
+                if cli.format.is_some() {
+                    eprintln!("{}", message);
+                } else {
+                    println!("{}", message);
+                }
+            };
+
+            if let Some(format) = cli.format {
+                display::format::display_prune_plan(&candidates, format)?;
+            } else if candidates.is_empty() {
+                println!("没有需要清理的分支（基准分支: {}）", base_name);
+                return Ok(());
+            } else {
+                println!("基准分支: {}", base_name);
+                if list {
+                    display::display_prune_plan_list(&candidates);
+                } else {
+                    display::display_prune_plan_table(&candidates);
+                }
+            }
+
+            if yes {
+                let deleted = commands::delete_branches(&repo, &candidates)?;
+                for candidate in &deleted {
+                    notice(format!(
+                        "已删除分支 {} (曾指向 {})",
+                        candidate.branch.name,
+                        candidate.branch.display_commit_id()
+                    ));
+                }
+                notice(format!("共删除 {} 个分支", deleted.len()));
+            } else if !candidates.is_empty() {
+                notice("以上为清理计划，使用 --yes 执行删除".to_string());
+            }
+        }
+        Commands::Branch {
+            command: None,
             list,
             table: _,
             local,
             remote,
+            sort,
         } => {
-            let (local_branches, remote_branches) = commands::get_branch_info(&repo)?;
+            let (mut local_branches, mut remote_branches) = commands::get_branch_info(&repo)?;
+            commands::sort_branches(&mut local_branches, sort);
+            commands::sort_branches(&mut remote_branches, sort);
             let show_local = !remote;
             let show_remote = !local;
 
-            if list {
+            if let Some(format) = cli.format {
+                display::format::display_branches(
+                    &local_branches,
+                    &remote_branches,
+                    show_local,
+                    show_remote,
+                    format,
+                )?;
+            } else if list {
                 display::display_branches_list(
                     &local_branches,
                     &remote_branches,
@@ -49,12 +115,194 @@ fn main() -> Result<()> {
                 );
             }
         }
-        Commands::Tag { list, table: _ } => {
-            let (tags, _) = commands::get_tag_info(&repo)?;
-            if list {
-                display::display_tags_list(&tags, &[], true, false);
+        Commands::Tag {
+            command:
+                Some(TagCommands::Create {
+                    name,
+                    message,
+                    commit,
+                    force,
+                }),
+            ..
+        } => {
+            let oid =
+                commands::create_tag(&repo, &name, message.as_deref(), commit.as_deref(), force)?;
+            println!("已创建标签 {} -> {}", name, &oid.to_string()[..8]);
+        }
+        Commands::Tag {
+            command:
+                Some(TagCommands::Delete {
+                    name,
+                    remote,
+                    remote_name,
+                }),
+            ..
+        } => {
+            if remote {
+                let remote = remote_name.as_deref().unwrap_or("origin");
+                commands::delete_remote_tag(&repo, remote, &name, cli.proxy.as_deref())?;
+                println!("已删除远程标签 {}/{}", remote, name);
+
+                // 本地标签存在时一并删除
+                if repo.find_reference(&format!("refs/tags/{}", name)).is_ok() {
+                    commands::delete_tag(&repo, &name)?;
+                    println!("已删除本地标签 {}", name);
+                }
+            } else {
+                commands::delete_tag(&repo, &name)?;
+                println!("已删除本地标签 {}", name);
+            }
+        }
+        Commands::Tag {
+            command:
+                Some(TagCommands::Push {
+                    name,
+                    all: _,
+                    remote,
+                    force,
+                }),
+            ..
+        } => {
+            let pushed =
+                commands::push_tags(&repo, &remote, name.as_deref(), force, cli.proxy.as_deref())?;
+            if pushed.is_empty() {
+                println!("远程仓库 {} 中的标签已是最新", remote);
+            } else {
+                println!(
+                    "已推送 {} 个标签到 {}: {}",
+                    pushed.len(),
+                    remote,
+                    pushed.join(", ")
+                );
+            }
+        }
+        Commands::Tag {
+            command: Some(TagCommands::Latest { pre }),
+            prefix,
+            ..
+        } => match commands::find_latest_version(&repo, prefix.as_deref(), pre)? {
+            Some(version) => println!("{}", version.tag_name()),
+            None => anyhow::bail!("没有找到版本标签"),
+        },
+        Commands::Tag {
+            command:
+                Some(TagCommands::Bump {
+                    level,
+                    preid,
+                    message,
+                    commit,
+                    dry_run,
+                }),
+            prefix,
+            ..
+        } => {
+            let next =
+                commands::next_tag_version(&repo, prefix.as_deref(), level, preid.as_deref())?;
+            let name = next.tag_name();
+
+            if dry_run {
+                println!("{}", name);
+            } else {
+                let oid = commands::create_tag(
+                    &repo,
+                    &name,
+                    message.as_deref(),
+                    commit.as_deref(),
+                    false,
+                )?;
+                println!("已创建标签 {} -> {}", name, &oid.to_string()[..8]);
+            }
+        }
+        Commands::Tag {
+            command: None,
+            list,
+            table: _,
+            local,
+            remote,
+            prefix,
+        } => {
+            // 只显示本地标签时不连接远程仓库
+            let (mut local_tags, mut remote_tags) =
+                commands::get_tag_info(&repo, cli.proxy.as_deref(), !local)?;
+            if let Some(prefix) = prefix {
+                let matches = |tag: &models::TagInfo| {
+                    models::TagVersion::parse(&tag.name).is_some_and(|v| v.prefix == prefix)
+                };
+                local_tags.retain(matches);
+                remote_tags.retain(matches);
+            }
+            let show_local = !remote;
+            let show_remote = !local;
+
+            if let Some(format) = cli.format {
+                display::format::display_tags(
+                    &local_tags,
+                    &remote_tags,
+                    show_local,
+                    show_remote,
+                    format,
+                )?;
+            } else if list {
+                display::display_tags_list(&local_tags, &remote_tags, show_local, show_remote);
+            } else {
+                display::display_tags_table(&local_tags, &remote_tags, show_local, show_remote);
+            }
+        }
+        Commands::Changelog { from, to, prefix } => {
+            let changelog =
+                commands::generate_changelog(&repo, from.as_deref(), &to, prefix.as_deref())?;
+
+            match cli.format {
+                Some(format) => display::format::display_changelog(&changelog, format)?,
+                None => display::display_changelog_markdown(&changelog),
+            }
+        }
+        Commands::Switch {
+            query,
+            stash,
+            force,
+        } => {
+            let mut repo = repo;
+            let dirty = commands::is_worktree_dirty(&repo)?;
+            if dirty && !stash && !force {
+                anyhow::bail!(
+                    "工作区有未提交的修改，请先提交，或使用 --stash 储藏、--force 丢弃这些修改"
+                );
+            }
+
+            let (local_branches, remote_branches) = commands::get_branch_info(&repo)?;
+            let selection = display::pick_branch(
+                &local_branches,
+                &remote_branches,
+                query.unwrap_or_default(),
+                |branch, is_remote| {
+                    let refname = if is_remote {
+                        format!("refs/remotes/{}", branch.name)
+                    } else {
+                        format!("refs/heads/{}", branch.name)
+                    };
+                    commands::recent_commits(&repo, &refname, 20).unwrap_or_default()
+                },
+            )?;
+
+            let Some((name, is_remote)) = selection else {
+                return Ok(());
+            };
+            if !is_remote && local_branches.iter().any(|b| b.is_head && b.name == name) {
+                println!("已在分支 {} 上", name);
+                return Ok(());
+            }
+
+            if dirty && stash {
+                commands::stash_changes(&mut repo)?;
+                println!("已储藏未提交的修改，使用 git stash pop 恢复");
+            }
+
+            let branch = commands::checkout_branch(&repo, &name, is_remote, force)?;
+            if is_remote {
+                println!("已切换到分支 {}（跟踪 {}）", branch, name);
             } else {
-                display::display_tags_table(&tags, &[], true, false);
+                println!("已切换到分支 {}", branch);
             }
         }
     }
@@ -1,11 +1,22 @@
 use colored::*;
+use serde::Serialize;
+
+use super::CommitInfo;
 
 /// 分支信息结构
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct BranchInfo {
     pub name: String,
     pub is_head: bool,
     pub upstream: Option<String>,
+    /// 领先上游的提交数
+    pub ahead: usize,
+    /// 落后上游的提交数
+    pub behind: usize,
+    /// 上游分支已被删除
+    pub gone: bool,
+    /// 分支最新提交
+    pub last_commit: Option<CommitInfo>,
 }
 
 impl BranchInfo {
@@ -14,6 +25,10 @@ impl BranchInfo {
             name,
             is_head,
             upstream,
+            ahead: 0,
+            behind: 0,
+            gone: false,
+            last_commit: None,
         }
     }
 
@@ -26,6 +41,92 @@ impl BranchInfo {
     }
 
     pub fn display_upstream(&self) -> String {
-        self.upstream.as_deref().unwrap_or("-").to_string()
+        match self.upstream {
+            Some(ref upstream) if self.gone => format!("{} {}", upstream, "(已删除)".red()),
+            Some(ref upstream) => upstream.to_string(),
+            None => "-".to_string(),
+        }
+    }
+
+    pub fn display_ahead(&self) -> String {
+        if self.upstream.is_none() || self.gone {
+            "-".to_string()
+        } else if self.ahead > 0 {
+            self.ahead.to_string().green().to_string()
+        } else {
+            "0".to_string()
+        }
+    }
+
+    pub fn display_behind(&self) -> String {
+        if self.upstream.is_none() || self.gone {
+            "-".to_string()
+        } else if self.behind > 0 {
+            self.behind.to_string().red().to_string()
+        } else {
+            "0".to_string()
+        }
+    }
+
+    pub fn display_commit_id(&self) -> String {
+        self.last_commit
+            .as_ref()
+            .map(|c| c.display_id())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    pub fn display_author(&self) -> String {
+        self.last_commit
+            .as_ref()
+            .map(|c| c.author.clone())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    pub fn display_time(&self) -> String {
+        self.last_commit
+            .as_ref()
+            .map(|c| c.display_time())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    pub fn display_summary(&self) -> String {
+        self.last_commit
+            .as_ref()
+            .map(|c| c.display_summary(50))
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// 列表模式下的提交摘要，例如 `a1b2c3d 更新 README (Yuki, 2 分钟前)`
+    pub fn display_last_commit(&self) -> Option<String> {
+        self.last_commit.as_ref().map(|c| {
+            format!(
+                "{} {} ({}, {})",
+                c.display_id(),
+                c.display_summary(50),
+                c.author,
+                c.display_time()
+            )
+        })
+    }
+
+    /// 类似 `git status` 的跟踪状态，例如 `[ahead 2, behind 1]`
+    pub fn display_tracking(&self) -> Option<String> {
+        if self.gone {
+            return Some("[gone]".red().to_string());
+        }
+
+        let mut parts = Vec::new();
+        if self.ahead > 0 {
+            parts.push(format!("ahead {}", self.ahead));
+        }
+        if self.behind > 0 {
+            parts.push(format!("behind {}", self.behind));
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(format!("[{}]", parts.join(", ")).yellow().to_string())
+        }
     }
 }
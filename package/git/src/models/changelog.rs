@@ -0,0 +1,184 @@
+use serde::Serialize;
+
+use super::CommitInfo;
+
+/// 变更日志分组，按显示顺序排列：(类型, 标题)
+///
+/// 不兼容变更单独成组，不属于以下类型的提交归入 `other`。
+const SECTIONS: &[(&str, &str)] = &[
+    ("breaking", "不兼容变更"),
+    ("feat", "新功能"),
+    ("fix", "问题修复"),
+    ("perf", "性能优化"),
+    ("refactor", "代码重构"),
+    ("revert", "回滚"),
+    ("docs", "文档"),
+    ("style", "代码格式"),
+    ("test", "测试"),
+    ("build", "构建"),
+    ("ci", "持续集成"),
+    ("chore", "杂项"),
+    ("other", "其他"),
+];
+
+/// 按 Conventional Commits 规范解析的提交
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogEntry {
+    /// 提交类型，例如 feat、fix；不符合规范的提交为 None
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+    pub commit: CommitInfo,
+}
+
+impl ChangelogEntry {
+    /// 解析提交说明，格式为 `type(scope)!: description`
+    ///
+    /// 说明正文或脚注中包含 `BREAKING CHANGE:` 时同样视为不兼容变更。
+    pub fn parse(commit: CommitInfo, message: &str) -> Self {
+        let summary = message.lines().next().unwrap_or("").trim();
+        let breaking_footer = message.lines().skip(1).any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+
+        match parse_header(summary) {
+            Some((kind, scope, breaking, description)) => Self {
+                kind: Some(kind.to_ascii_lowercase()),
+                scope: scope.map(String::from),
+                breaking: breaking || breaking_footer,
+                description: description.to_string(),
+                commit,
+            },
+            None => Self {
+                kind: None,
+                scope: None,
+                breaking: breaking_footer,
+                description: summary.to_string(),
+                commit,
+            },
+        }
+    }
+
+    /// 所属分组
+    pub fn section(&self) -> &'static str {
+        if self.breaking {
+            return "breaking";
+        }
+        let kind = self.kind.as_deref().unwrap_or("other");
+        SECTIONS
+            .iter()
+            .map(|(section, _)| *section)
+            .find(|section| *section == kind)
+            .unwrap_or("other")
+    }
+
+    /// Markdown 列表项，例如 `- **cli:** 添加 --prefix 参数 (a1b2c3d)`
+    pub fn display_markdown(&self) -> String {
+        let mut line = String::from("- ");
+        if let Some(ref scope) = self.scope {
+            line.push_str(&format!("**{}:** ", scope));
+        }
+        line.push_str(&self.description);
+        line.push_str(&format!(" ({})", self.commit.short_id));
+        line
+    }
+}
+
+/// 解析提交标题，返回 (类型, 范围, 是否不兼容, 描述)
+fn parse_header(summary: &str) -> Option<(&str, Option<&str>, bool, &str)> {
+    let (head, description) = summary.split_once(": ")?;
+    let description = description.trim();
+    if description.is_empty() {
+        return None;
+    }
+
+    let (head, breaking) = match head.strip_suffix('!') {
+        Some(head) => (head, true),
+        None => (head, false),
+    };
+
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+        None => (head, None),
+    };
+
+    let valid_kind = !kind.is_empty()
+        && kind
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_kind || scope.is_some_and(|s| s.is_empty() || s.contains(['(', ')'])) {
+        return None;
+    }
+
+    Some((kind, scope, breaking, description))
+}
+
+/// 变更日志中的一个分组
+#[derive(Debug, Serialize)]
+pub struct ChangelogSection {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: &'static str,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// 两个引用之间的变更日志
+#[derive(Debug, Serialize)]
+pub struct Changelog {
+    /// 起始引用，None 表示从第一个提交开始
+    pub from: Option<String>,
+    pub to: String,
+    /// `to` 为版本标签时的版本名
+    pub version: Option<String>,
+    /// `to` 指向提交的时间（Unix 时间戳，秒）
+    pub time: i64,
+    pub sections: Vec<ChangelogSection>,
+}
+
+impl Changelog {
+    /// 按类型分组，分组内保持提交顺序，空分组不会输出
+    pub fn new(
+        from: Option<String>,
+        to: String,
+        version: Option<String>,
+        time: i64,
+        entries: Vec<ChangelogEntry>,
+    ) -> Self {
+        let mut sections: Vec<ChangelogSection> = SECTIONS
+            .iter()
+            .map(|(kind, title)| ChangelogSection {
+                kind,
+                title,
+                entries: Vec::new(),
+            })
+            .collect();
+
+        for entry in entries {
+            let section = entry.section();
+            if let Some(group) = sections.iter_mut().find(|s| s.kind == section) {
+                group.entries.push(entry);
+            }
+        }
+        sections.retain(|s| !s.entries.is_empty());
+
+        Self {
+            from,
+            to,
+            version,
+            time,
+            sections,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&ChangelogSection, &ChangelogEntry)> {
+        self.sections
+            .iter()
+            .flat_map(|section| section.entries.iter().map(move |entry| (section, entry)))
+    }
+}
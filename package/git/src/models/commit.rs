@@ -0,0 +1,89 @@
+use colored::*;
+use git2::Commit;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 提交信息结构
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub author: String,
+    /// 提交时间（Unix 时间戳，秒）
+    pub time: i64,
+    pub summary: String,
+}
+
+impl CommitInfo {
+    pub fn from_commit(commit: &Commit) -> Self {
+        let id = commit.id().to_string();
+        let short_id = commit
+            .as_object()
+            .short_id()
+            .ok()
+            .and_then(|buf| buf.as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| id[..7].to_string());
+
+        Self {
+            id,
+            short_id,
+            author: commit.author().name().unwrap_or("").to_string(),
+            time: commit.committer().when().seconds(),
+            summary: commit.summary().unwrap_or("").to_string(),
+        }
+    }
+
+    pub fn display_id(&self) -> String {
+        self.short_id.yellow().to_string()
+    }
+
+    pub fn display_time(&self) -> String {
+        format_relative_time(self.time)
+    }
+
+    /// 截断过长的提交说明，避免表格过宽
+    pub fn display_summary(&self, max_chars: usize) -> String {
+        if self.summary.chars().count() > max_chars {
+            let truncated: String = self.summary.chars().take(max_chars).collect();
+            format!("{}…", truncated)
+        } else {
+            self.summary.clone()
+        }
+    }
+}
+
+/// 将时间戳格式化为相对时间，例如 "2 分钟前"
+fn format_relative_time(time: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(time);
+    let diff = (now - time).max(0);
+
+    match diff {
+        0..=59 => "刚刚".to_string(),
+        60..=3599 => format!("{} 分钟前", diff / 60),
+        3600..=86399 => format!("{} 小时前", diff / 3600),
+        86400..=2591999 => format!("{} 天前", diff / 86400),
+        2592000..=31535999 => format!("{} 个月前", diff / 2592000),
+        _ => format!("{} 年前", diff / 31536000),
+    }
+}
+
+/// 将时间戳格式化为 UTC 日期，例如 "2024-03-01"
+///
+/// 按 Howard Hinnant 的 civil_from_days 算法换算，避免引入日期库。
+pub fn format_date(time: i64) -> String {
+    let days = time.div_euclid(86400);
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
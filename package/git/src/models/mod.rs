@@ -1,5 +1,13 @@
 mod branch;
+mod changelog;
+mod commit;
+mod prune;
 mod tag;
+mod version;
 
 pub use branch::BranchInfo;
-pub use tag::TagInfo;
+pub use changelog::{Changelog, ChangelogEntry};
+pub use commit::{format_date, CommitInfo};
+pub use prune::{PruneCandidate, PruneReason};
+pub use tag::{TagInfo, TagStatus};
+pub use version::{compare_tag_names, TagVersion};
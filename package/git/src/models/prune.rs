@@ -0,0 +1,63 @@
+use colored::*;
+use serde::{Serialize, Serializer};
+
+use super::BranchInfo;
+
+/// 分支被清理的原因
+///
+/// 所有输出格式都序列化为 [`PruneReason::code`]，例如 `merged`、`stale:90`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    /// 已完全合并到基准分支
+    Merged,
+    /// 上游分支已被删除
+    Gone,
+    /// 超过指定天数没有新提交
+    Stale(u64),
+}
+
+impl PruneReason {
+    /// 机器可读的原因名称，长期未更新时附带天数
+    pub fn code(&self) -> String {
+        match self {
+            PruneReason::Merged => "merged".to_string(),
+            PruneReason::Gone => "gone".to_string(),
+            PruneReason::Stale(days) => format!("stale:{}", days),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            PruneReason::Merged => "已合并".to_string(),
+            PruneReason::Gone => "上游已删除".to_string(),
+            PruneReason::Stale(days) => format!("{} 天未更新", days),
+        }
+    }
+}
+
+impl Serialize for PruneReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.code())
+    }
+}
+
+/// 待清理的分支
+#[derive(Debug, Serialize)]
+pub struct PruneCandidate {
+    pub branch: BranchInfo,
+    pub reasons: Vec<PruneReason>,
+}
+
+impl PruneCandidate {
+    pub fn display_reasons(&self) -> String {
+        let reasons: Vec<String> = self.reasons.iter().map(|r| r.label()).collect();
+        let text = reasons.join(", ");
+
+        // 未合并的分支删除后提交可能丢失，用红色提示
+        if self.reasons.contains(&PruneReason::Merged) {
+            text.yellow().to_string()
+        } else {
+            text.red().to_string()
+        }
+    }
+}
@@ -1,11 +1,54 @@
 use colored::*;
+use serde::Serialize;
+
+/// 标签同步状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagStatus {
+    /// 本地和远程都存在且指向同一提交
+    Synced,
+    /// 仅存在于本地
+    LocalOnly,
+    /// 仅存在于远程
+    RemoteOnly,
+    /// 本地和远程都存在但指向不同提交
+    Diverged,
+    /// 没有连接远程仓库，同步状态未知
+    Unchecked,
+}
+
+impl TagStatus {
+    /// 机器可读的状态名称
+    pub fn code(&self) -> &'static str {
+        match self {
+            TagStatus::Synced => "synced",
+            TagStatus::LocalOnly => "local_only",
+            TagStatus::RemoteOnly => "remote_only",
+            TagStatus::Diverged => "diverged",
+            TagStatus::Unchecked => "unchecked",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TagStatus::Synced => "已同步",
+            TagStatus::LocalOnly => "仅本地",
+            TagStatus::RemoteOnly => "仅远程",
+            TagStatus::Diverged => "不一致",
+            TagStatus::Unchecked => "未检查",
+        }
+    }
+}
 
 /// tag信息结构
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TagInfo {
     pub name: String,
     pub commit: String,
     pub message: Option<String>,
+    /// 远程名称，本地标签为 None
+    pub remote: Option<String>,
+    pub status: TagStatus,
 }
 
 impl TagInfo {
@@ -14,6 +57,18 @@ impl TagInfo {
             name,
             commit,
             message,
+            remote: None,
+            status: TagStatus::LocalOnly,
+        }
+    }
+
+    pub fn remote(name: String, commit: String, remote: String) -> Self {
+        Self {
+            name,
+            commit,
+            message: None,
+            remote: Some(remote),
+            status: TagStatus::RemoteOnly,
         }
     }
 
@@ -21,7 +76,10 @@ impl TagInfo {
         if is_local {
             self.name.green().to_string()
         } else {
-            self.name.to_string()
+            match self.remote {
+                Some(ref remote) => format!("{}/{}", remote, self.name),
+                None => self.name.to_string(),
+            }
         }
     }
 
@@ -32,4 +90,14 @@ impl TagInfo {
     pub fn display_message(&self) -> String {
         self.message.as_deref().unwrap_or("-").to_string()
     }
+
+    pub fn display_status(&self) -> String {
+        let label = self.status.label();
+        match self.status {
+            TagStatus::Synced => label.green().to_string(),
+            TagStatus::LocalOnly | TagStatus::RemoteOnly => label.yellow().to_string(),
+            TagStatus::Diverged => label.red().to_string(),
+            TagStatus::Unchecked => label.dimmed().to_string(),
+        }
+    }
 }
@@ -0,0 +1,125 @@
+use semver::{BuildMetadata, Prerelease, Version};
+use std::cmp::Ordering;
+
+use crate::cli::BumpLevel;
+
+/// 带前缀的语义化版本标签，例如 `v1.2.0`、`ziper-v0.1.1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagVersion {
+    pub prefix: String,
+    pub version: Version,
+}
+
+impl TagVersion {
+    pub fn new(prefix: &str, version: Version) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            version,
+        }
+    }
+
+    /// 解析标签名，前缀为版本号之前的部分
+    ///
+    /// 从左到右查找第一个能解析为完整语义化版本的数字起点，
+    /// 因此 `ziper-v0.1.1` 的前缀为 `ziper-v`，`1.0.0` 的前缀为空。
+    pub fn parse(name: &str) -> Option<Self> {
+        name.char_indices()
+            .filter(|(i, c)| {
+                c.is_ascii_digit() && !name[..*i].ends_with(|p: char| p.is_ascii_digit())
+            })
+            .find_map(|(i, _)| {
+                Version::parse(&name[i..])
+                    .ok()
+                    .map(|version| Self::new(&name[..i], version))
+            })
+    }
+
+    pub fn tag_name(&self) -> String {
+        format!("{}{}", self.prefix, self.version)
+    }
+
+    /// 计算下一个版本
+    ///
+    /// 与 npm 的规则一致：预发布版本递增 patch/minor/major 时，
+    /// 如果对应的正式版本尚未发布，直接去掉预发布标识。
+    /// `preid` 用于 prerelease，例如 `rc` 生成 `1.0.1-rc.0`。
+    pub fn bump(&self, level: BumpLevel, preid: Option<&str>) -> Result<Self, semver::Error> {
+        let current = &self.version;
+        let mut next = Version::new(current.major, current.minor, current.patch);
+        let is_pre = !current.pre.is_empty();
+
+        match level {
+            BumpLevel::Major => {
+                if !(is_pre && current.minor == 0 && current.patch == 0) {
+                    next = Version::new(current.major + 1, 0, 0);
+                }
+            }
+            BumpLevel::Minor => {
+                if !(is_pre && current.patch == 0) {
+                    next = Version::new(current.major, current.minor + 1, 0);
+                }
+            }
+            BumpLevel::Patch => {
+                if !is_pre {
+                    next = Version::new(current.major, current.minor, current.patch + 1);
+                }
+            }
+            BumpLevel::Prerelease => {
+                next.pre = next_prerelease(current, preid)?;
+                if !is_pre {
+                    next.patch += 1;
+                }
+            }
+        }
+
+        next.build = BuildMetadata::EMPTY;
+        Ok(Self::new(&self.prefix, next))
+    }
+}
+
+/// 递增预发布标识
+///
+/// - `1.0.0-rc.1` -> `rc.2`
+/// - `1.0.0-rc` -> `rc.0`
+/// - 正式版本或 preid 不同时 -> `<preid>.0`（未指定 preid 时为 `0`）
+fn next_prerelease(current: &Version, preid: Option<&str>) -> Result<Prerelease, semver::Error> {
+    let pre = current.pre.as_str();
+
+    if !pre.is_empty() {
+        let (id, number) = match pre.rsplit_once('.') {
+            Some((id, n)) if n.parse::<u64>().is_ok() => (Some(id), n.parse::<u64>().ok()),
+            _ if pre.parse::<u64>().is_ok() => (None, pre.parse::<u64>().ok()),
+            _ => (Some(pre), None),
+        };
+
+        if preid.is_none() || preid == id {
+            return Prerelease::new(&match (id, number) {
+                (Some(id), Some(n)) => format!("{}.{}", id, n + 1),
+                (Some(id), None) => format!("{}.0", id),
+                (None, Some(n)) => (n + 1).to_string(),
+                (None, None) => "0".to_string(),
+            });
+        }
+    }
+
+    Prerelease::new(&match preid {
+        Some(id) => format!("{}.0", id),
+        None => "0".to_string(),
+    })
+}
+
+/// 按语义化版本比较两个标签名
+///
+/// 先按前缀分组，同一前缀内按版本号排序；无法解析的标签按名称排在对应位置。
+pub fn compare_tag_names(a: &str, b: &str) -> Ordering {
+    match (TagVersion::parse(a), TagVersion::parse(b)) {
+        (Some(va), Some(vb)) => va
+            .prefix
+            .cmp(&vb.prefix)
+            .then_with(|| va.version.cmp(&vb.version))
+            .then_with(|| a.cmp(b)),
+        (Some(va), None) => va.prefix.as_str().cmp(b).then(Ordering::Less),
+        (None, Some(vb)) => a.cmp(vb.prefix.as_str()).then(Ordering::Greater),
+        (None, None) => a.cmp(b),
+    }
+}
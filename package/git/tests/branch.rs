@@ -0,0 +1,176 @@
+mod common;
+
+use git2::{BranchType, FetchOptions, FetchPrune, Repository};
+use serde_json::Value;
+use tempfile::TempDir;
+
+use common::{commit, commit_as, commit_on, giter_json, init_bare, init_repo};
+
+/// 从远程仓库获取所有分支，并清理远程已删除的分支
+fn fetch(repo: &Repository) {
+    let mut options = FetchOptions::new();
+    options.prune(FetchPrune::On);
+    repo.find_remote("origin")
+        .unwrap()
+        .fetch(
+            &["+refs/heads/*:refs/remotes/origin/*"],
+            Some(&mut options),
+            None,
+        )
+        .unwrap();
+}
+
+/// 推送分支并设置上游
+fn push_tracking(repo: &Repository, branch: &str) {
+    let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+    repo.find_remote("origin")
+        .unwrap()
+        .push(&[refspec.as_str()], None)
+        .unwrap();
+    fetch(repo);
+    repo.find_branch(branch, BranchType::Local)
+        .unwrap()
+        .set_upstream(Some(&format!("origin/{}", branch)))
+        .unwrap();
+}
+
+/// 按名称查找分支
+fn branch<'a>(branches: &'a Value, name: &str) -> &'a Value {
+    branches
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|branch| branch["name"] == name)
+        .unwrap_or_else(|| panic!("没有找到分支 {}: {}", name, branches))
+}
+
+#[test]
+fn counts_commits_ahead_and_behind_upstream() {
+    let (dir, repo) = init_repo();
+    let remote = init_bare();
+    repo.remote("origin", remote.path().to_str().unwrap())
+        .unwrap();
+    commit(&repo, "first", 1_700_000_000);
+    push_tracking(&repo, "main");
+
+    // 本地领先两个提交，远程领先一个提交
+    commit(&repo, "local 1", 1_700_000_100);
+    commit(&repo, "local 2", 1_700_000_200);
+    let bare = Repository::open_bare(remote.path()).unwrap();
+    commit_on(&bare, "main", None, "remote", 1_700_000_300);
+    fetch(&repo);
+
+    let listing = giter_json(dir.path(), &["branch"]);
+    let main = branch(&listing["local"], "main");
+    assert_eq!(main["upstream"], "origin/main");
+    assert_eq!(main["ahead"], 2);
+    assert_eq!(main["behind"], 1);
+    assert_eq!(main["gone"], false);
+
+    let tracking = branch(&listing["remote"], "origin/main");
+    assert_eq!(tracking["last_commit"]["summary"], "remote");
+}
+
+#[test]
+fn marks_deleted_upstream_as_gone() {
+    let (dir, repo) = init_repo();
+    let remote = init_bare();
+    repo.remote("origin", remote.path().to_str().unwrap())
+        .unwrap();
+    let head = commit(&repo, "first", 1_700_000_000);
+    commit_on(&repo, "feature", Some(head), "feature", 1_700_000_100);
+    push_tracking(&repo, "main");
+    push_tracking(&repo, "feature");
+
+    let listing = giter_json(dir.path(), &["branch"]);
+    assert_eq!(branch(&listing["local"], "feature")["gone"], false);
+
+    // 远程删除分支后获取并清理
+    Repository::open_bare(remote.path())
+        .unwrap()
+        .find_reference("refs/heads/feature")
+        .unwrap()
+        .delete()
+        .unwrap();
+    fetch(&repo);
+
+    let listing = giter_json(dir.path(), &["branch"]);
+    let feature = branch(&listing["local"], "feature");
+    assert_eq!(feature["gone"], true);
+    assert_eq!(feature["upstream"], "origin/feature");
+    assert_eq!(feature["ahead"], 0);
+    assert_eq!(feature["behind"], 0);
+    assert_eq!(branch(&listing["local"], "main")["gone"], false);
+}
+
+/// 创建由不同作者在不同时间提交的分支，HEAD 位于 main
+///
+/// | 分支 | 作者 | 时间 |
+/// | --- | --- | --- |
+/// | main | Tester | 1_700_000_000 |
+/// | alpha | carol | 1_700_000_300 |
+/// | beta | Bob | 1_700_000_100 |
+/// | gamma | alice | 1_700_000_200 |
+/// | delta | Bob | 1_700_000_400 |
+fn authored_branches() -> TempDir {
+    let (dir, repo) = init_repo();
+    let head = commit(&repo, "init", 1_700_000_000);
+    for (branch, author, time) in [
+        ("alpha", "carol", 1_700_000_300),
+        ("beta", "Bob", 1_700_000_100),
+        ("gamma", "alice", 1_700_000_200),
+        ("delta", "Bob", 1_700_000_400),
+    ] {
+        let message = format!("feat: {}", branch);
+        commit_as(&repo, branch, Some(head), author, &message, time);
+    }
+    dir
+}
+
+fn local_names(dir: &TempDir, sort: &str) -> Vec<String> {
+    let listing = giter_json(dir.path(), &["branch", "--local", "--sort", sort]);
+    listing["local"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|branch| branch["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn sorts_branches_by_name_date_and_author() {
+    let dir = authored_branches();
+
+    // HEAD 分支优先
+    assert_eq!(
+        local_names(&dir, "name"),
+        ["main", "alpha", "beta", "delta", "gamma"]
+    );
+    assert_eq!(
+        local_names(&dir, "date"),
+        ["delta", "alpha", "gamma", "beta", "main"]
+    );
+    // 作者不区分大小写，同一作者按时间从新到旧
+    assert_eq!(
+        local_names(&dir, "author"),
+        ["gamma", "delta", "beta", "alpha", "main"]
+    );
+}
+
+#[test]
+fn reports_last_commit_of_each_branch() {
+    let dir = authored_branches();
+    let listing = giter_json(dir.path(), &["branch", "--local"]);
+
+    let gamma = &branch(&listing["local"], "gamma")["last_commit"];
+    assert_eq!(gamma["author"], "alice");
+    assert_eq!(gamma["time"], 1_700_000_200);
+    assert_eq!(gamma["summary"], "feat: gamma");
+    let id = gamma["id"].as_str().unwrap();
+    assert_eq!(id.len(), 40);
+    assert!(id.starts_with(gamma["short_id"].as_str().unwrap()));
+
+    let main = &branch(&listing["local"], "main")["last_commit"];
+    assert_eq!(main["author"], "Tester");
+    assert_eq!(main["time"], 1_700_000_000);
+}
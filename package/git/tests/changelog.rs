@@ -0,0 +1,63 @@
+mod common;
+
+use git2::{Oid, Repository};
+use tempfile::TempDir;
+
+use common::{commit, commit_on, giter, giter_json, init_repo};
+
+fn tag(repo: &Repository, name: &str, oid: Oid) {
+    let object = repo.find_object(oid, None).unwrap();
+    repo.tag_lightweight(name, &object, false).unwrap();
+}
+
+/// 两个项目共用一个仓库：main 上发布 v 前缀的版本，
+/// 从 main 分出的 ziper 分支上发布 ziper-v 前缀的版本
+fn monorepo() -> (TempDir, Repository) {
+    let (dir, repo) = init_repo();
+    let base = commit(&repo, "chore: init", 1_700_000_000);
+    tag(&repo, "v1.0.0", base);
+    let release = commit(&repo, "feat: app release", 1_700_000_100);
+    tag(&repo, "v1.1.0", release);
+    commit(&repo, "fix: app bug", 1_700_000_200);
+
+    let ziper = commit_on(&repo, "ziper", Some(base), "feat: zip", 1_700_000_300);
+    tag(&repo, "ziper-v0.1.0", ziper);
+    commit_on(&repo, "ziper", None, "fix: zip bug", 1_700_000_400);
+    (dir, repo)
+}
+
+fn from(repo: &Repository, args: &[&str]) -> serde_json::Value {
+    let mut all = vec!["changelog"];
+    all.extend(args);
+    giter_json(repo.workdir().unwrap(), &all)["from"].clone()
+}
+
+#[test]
+fn defaults_to_prefix_of_nearest_tag() {
+    let (_dir, repo) = monorepo();
+
+    assert_eq!(from(&repo, &["--to", "main"]), "v1.1.0");
+    assert_eq!(from(&repo, &["--to", "ziper"]), "ziper-v0.1.0");
+    // --to 指向的提交本身带有标签时使用该标签的前缀
+    assert_eq!(from(&repo, &["--to", "main~1"]), "v1.0.0");
+    assert_eq!(from(&repo, &["--to", "ziper", "--prefix", "v"]), "v1.0.0");
+}
+
+#[test]
+fn ambiguous_nearest_prefix_is_rejected() {
+    let (dir, repo) = monorepo();
+    // 同一个提交上有两种前缀的标签
+    let head = commit(&repo, "chore: both", 1_700_000_500);
+    tag(&repo, "ziper-v0.2.0", head);
+    tag(&repo, "v1.2.0", head);
+    commit(&repo, "fix: after", 1_700_000_600);
+
+    let output = giter(dir.path(), &["changelog", "--to", "main"], &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--prefix"));
+
+    assert_eq!(
+        from(&repo, &["--to", "main", "--prefix", "ziper-v"]),
+        "ziper-v0.2.0"
+    );
+}
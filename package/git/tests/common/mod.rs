@@ -0,0 +1,123 @@
+//! 集成测试共用的辅助函数
+
+// 每个测试文件只用到其中一部分
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use git2::{Oid, Repository, RepositoryInitOptions, Signature, Time};
+use tempfile::TempDir;
+
+/// 在隔离的环境中运行 giter，避免受到本机代理和 git 配置的影响
+pub fn giter(repo: &Path, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_giter"));
+    command
+        .arg("-p")
+        .arg(repo)
+        .args(args)
+        .env("HOME", repo)
+        .env("XDG_CONFIG_HOME", repo)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("NO_COLOR", "1");
+    for name in [
+        "HTTP_PROXY",
+        "http_proxy",
+        "HTTPS_PROXY",
+        "https_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+        "NO_PROXY",
+        "no_proxy",
+    ] {
+        command.env_remove(name);
+    }
+    command.envs(envs.iter().copied());
+    command.output().unwrap()
+}
+
+/// 以 JSON 格式运行 giter 并解析输出
+pub fn giter_json(repo: &Path, args: &[&str]) -> serde_json::Value {
+    let mut all = vec!["--format", "json"];
+    all.extend(args);
+    let output = giter(repo, &all, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// 创建 HEAD 指向 main 分支的空仓库
+pub fn init_repo() -> (TempDir, Repository) {
+    let dir = TempDir::new().unwrap();
+    let repo = Repository::init_opts(
+        dir.path(),
+        RepositoryInitOptions::new().initial_head("main"),
+    )
+    .unwrap();
+    (dir, repo)
+}
+
+/// 创建用作远程仓库的裸仓库
+pub fn init_bare() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    Repository::init_bare(dir.path()).unwrap();
+    dir
+}
+
+/// 在分支上提交一次修改，`time` 为提交时间的 Unix 时间戳，返回提交 ID
+///
+/// 分支不存在时以 `parent` 为父提交创建，`parent` 为 None 时创建根提交。
+pub fn commit_on(
+    repo: &Repository,
+    branch: &str,
+    parent: Option<Oid>,
+    message: &str,
+    time: i64,
+) -> Oid {
+    commit_as(repo, branch, parent, "Tester", message, time)
+}
+
+/// 以指定作者在分支上提交一次修改，参数含义与 [`commit_on`] 相同
+pub fn commit_as(
+    repo: &Repository,
+    branch: &str,
+    parent: Option<Oid>,
+    author: &str,
+    message: &str,
+    time: i64,
+) -> Oid {
+    let email = format!("{}@example.com", author.to_lowercase());
+    let signature = Signature::new(author, &email, &Time::new(time, 0)).unwrap();
+    let refname = format!("refs/heads/{}", branch);
+    let parent = match repo.find_reference(&refname) {
+        Ok(reference) => Some(reference.peel_to_commit().unwrap()),
+        Err(_) => parent.map(|oid| repo.find_commit(oid).unwrap()),
+    };
+
+    let blob = repo.blob(message.as_bytes()).unwrap();
+    let base = parent.as_ref().map(|commit| commit.tree().unwrap());
+    let mut builder = repo.treebuilder(base.as_ref()).unwrap();
+    builder
+        .insert(format!("{}.txt", branch.replace('/', "-")), blob, 0o100644)
+        .unwrap();
+    let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(
+        Some(&refname),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .unwrap()
+}
+
+/// 在 main 分支上提交一次修改
+pub fn commit(repo: &Repository, message: &str, time: i64) -> Oid {
+    commit_on(repo, "main", None, message, time)
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use git2::Repository;
+use tempfile::TempDir;
+
+use common::giter;
+
+const REMOTE_URL: &str = "https://example.invalid/repo.git";
+
+/// 创建一个远程指向 REMOTE_URL 的仓库
+fn setup_repo() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    repo.remote("origin", REMOTE_URL).unwrap();
+    dir
+}
+
+/// 代理替身：接受一个连接，读取初始数据后返回
+///
+/// `respond` 根据收到的数据生成响应，用于模拟 HTTP 或 SOCKS5 代理。
+fn proxy_stand_in(respond: fn(&[u8]) -> Option<Vec<u8>>) -> (String, JoinHandle<Option<Vec<u8>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    listener.set_nonblocking(true).unwrap();
+
+    let handle = thread::spawn(move || {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut stream = loop {
+            match listener.accept() {
+                Ok((stream, _)) => break stream,
+                Err(_) if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+                Err(_) => return None,
+            }
+        };
+        stream.set_nonblocking(false).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let mut received = Vec::new();
+        let mut buf = [0u8; 4096];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            received.extend_from_slice(&buf[..n]);
+            match respond(&received) {
+                Some(reply) => {
+                    let _ = stream.write_all(&reply);
+                    if received.ends_with(b"\r\n\r\n") {
+                        break;
+                    }
+                }
+                None => continue,
+            }
+        }
+        Some(received)
+    });
+
+    (addr, handle)
+}
+
+fn http_respond(received: &[u8]) -> Option<Vec<u8>> {
+    received.ends_with(b"\r\n\r\n").then(|| {
+        b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec()
+    })
+}
+
+fn received_text(handle: JoinHandle<Option<Vec<u8>>>) -> Option<String> {
+    handle
+        .join()
+        .unwrap()
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[test]
+fn proxy_option_is_used_for_ls_remote() {
+    let repo = setup_repo();
+    let (addr, handle) = proxy_stand_in(http_respond);
+
+    let output = giter(
+        repo.path(),
+        &["--proxy", &format!("http://{}", addr), "tag", "--remote"],
+        &[],
+    );
+    assert!(output.status.success());
+
+    let request = received_text(handle).expect("proxy was not contacted");
+    assert!(
+        request.starts_with("CONNECT example.invalid:443 "),
+        "unexpected request: {}",
+        request
+    );
+}
+
+#[test]
+fn proxy_without_scheme_is_treated_as_http() {
+    let repo = setup_repo();
+    let (addr, handle) = proxy_stand_in(http_respond);
+
+    giter(repo.path(), &["tag", "--remote", "-x", &addr], &[]);
+
+    assert!(received_text(handle).is_some());
+}
+
+#[test]
+fn git_config_http_proxy_is_used() {
+    let repo = setup_repo();
+    let (addr, handle) = proxy_stand_in(http_respond);
+    Repository::open(repo.path())
+        .unwrap()
+        .config()
+        .unwrap()
+        .set_str("http.proxy", &format!("http://{}", addr))
+        .unwrap();
+
+    giter(repo.path(), &["tag", "--remote"], &[]);
+
+    assert!(received_text(handle).is_some());
+}
+
+#[test]
+fn environment_proxy_is_used() {
+    let repo = setup_repo();
+    let (addr, handle) = proxy_stand_in(http_respond);
+
+    giter(
+        repo.path(),
+        &["tag", "--remote"],
+        &[("HTTPS_PROXY", &format!("http://{}", addr))],
+    );
+
+    assert!(received_text(handle).is_some());
+}
+
+#[test]
+fn no_proxy_bypasses_environment_proxy() {
+    let repo = setup_repo();
+    let (addr, handle) = proxy_stand_in(http_respond);
+
+    giter(
+        repo.path(),
+        &["tag", "--remote"],
+        &[
+            ("HTTPS_PROXY", &format!("http://{}", addr)),
+            ("NO_PROXY", "localhost,.invalid"),
+        ],
+    );
+
+    assert!(received_text(handle).is_none());
+}
+
+#[test]
+fn socks5_proxy_is_bridged() {
+    // 模拟 SOCKS5 服务器：接受无认证握手，记录连接请求后返回失败
+    fn socks_respond(received: &[u8]) -> Option<Vec<u8>> {
+        match received {
+            [5, 1, 0] => Some(vec![5, 0]),
+            [5, 1, 0, 5, 1, 0, 3, len, rest @ ..] if rest.len() == *len as usize + 2 => {
+                Some(vec![5, 1, 0, 1, 0, 0, 0, 0, 0, 0])
+            }
+            _ => None,
+        }
+    }
+
+    let repo = setup_repo();
+    let (addr, handle) = proxy_stand_in(socks_respond);
+
+    giter(
+        repo.path(),
+        &["--proxy", &format!("socks5h://{}", addr), "tag", "--remote"],
+        &[],
+    );
+
+    let received = handle
+        .join()
+        .unwrap()
+        .expect("socks proxy was not contacted");
+    let mut expected = vec![5, 1, 0, 5, 1, 0, 3, 15];
+    expected.extend_from_slice(b"example.invalid");
+    expected.extend_from_slice(&443u16.to_be_bytes());
+    assert_eq!(received, expected);
+}
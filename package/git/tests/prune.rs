@@ -0,0 +1,218 @@
+mod common;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use git2::{BranchType, Repository};
+use serde_json::Value;
+use tempfile::TempDir;
+
+use common::{commit, commit_on, giter, giter_json, init_repo};
+
+const DAY: i64 = 86400;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// 创建包含各种待清理分支的仓库，基准分支为 main，HEAD 位于 feature/head
+///
+/// - feature/merged：已合并到 main
+/// - feature/gone：上游 origin/feature/gone 已删除
+/// - feature/stale：100 天没有新提交
+/// - feature/active：最近有提交，不应被清理
+/// - feature/head：已合并但为当前分支，不应被清理
+fn prepare() -> (TempDir, Repository) {
+    let (dir, repo) = init_repo();
+    let now = now();
+    let first = commit(&repo, "first", now - 2 * DAY);
+    let second = commit(&repo, "second", now - DAY);
+
+    for name in ["feature/merged", "feature/head"] {
+        repo.branch(name, &repo.find_commit(first).unwrap(), false)
+            .unwrap();
+    }
+    commit_on(&repo, "feature/gone", Some(second), "gone", now - DAY);
+    commit_on(
+        &repo,
+        "feature/stale",
+        Some(second),
+        "stale",
+        now - 100 * DAY,
+    );
+    commit_on(&repo, "feature/active", Some(second), "active", now);
+
+    // 上游指向不存在的远程跟踪分支
+    repo.remote("origin", "https://example.invalid/repo.git")
+        .unwrap();
+    let mut config = repo.config().unwrap();
+    config
+        .set_str("branch.feature/gone.remote", "origin")
+        .unwrap();
+    config
+        .set_str("branch.feature/gone.merge", "refs/heads/feature/gone")
+        .unwrap();
+
+    repo.set_head("refs/heads/feature/head").unwrap();
+    (dir, repo)
+}
+
+/// 清理计划中各分支的原因
+fn plan(repo: &Repository, args: &[&str]) -> Vec<(String, Vec<String>)> {
+    let mut all = vec!["branch", "prune"];
+    all.extend(args);
+    let plan = giter_json(repo.workdir().unwrap(), &all);
+    plan.as_array()
+        .unwrap()
+        .iter()
+        .map(|candidate| {
+            let reasons = candidate["reasons"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(reason_kind)
+                .collect();
+            (
+                candidate["branch"]["name"].as_str().unwrap().to_string(),
+                reasons,
+            )
+        })
+        .collect()
+}
+
+fn plan_names(repo: &Repository, args: &[&str]) -> Vec<String> {
+    plan(repo, args).into_iter().map(|(name, _)| name).collect()
+}
+
+/// 去掉 `stale:<天数>` 中的天数
+fn reason_kind(reason: &Value) -> String {
+    let code = reason.as_str().unwrap();
+    code.split(':').next().unwrap().to_string()
+}
+
+fn branch_exists(repo: &Repository, name: &str) -> bool {
+    repo.find_branch(name, BranchType::Local).is_ok()
+}
+
+#[test]
+fn finds_merged_and_gone_branches() {
+    let (_dir, repo) = prepare();
+    let plan = plan(&repo, &[]);
+    assert_eq!(
+        plan,
+        vec![
+            ("feature/gone".to_string(), vec!["gone".to_string()]),
+            ("feature/merged".to_string(), vec!["merged".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn finds_stale_branches_past_threshold() {
+    let (_dir, repo) = prepare();
+    let plan = plan(&repo, &["--stale-days", "30"]);
+    let names: Vec<_> = plan.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["feature/gone", "feature/merged", "feature/stale"]);
+    assert_eq!(plan[2].1, ["stale"]);
+
+    // 未达到天数的分支不会被清理
+    let plan = plan_names(&repo, &["--stale-days", "101"]);
+    assert!(!plan.contains(&"feature/stale".to_string()));
+}
+
+#[test]
+fn head_branch_is_never_pruned() {
+    let (dir, repo) = prepare();
+    assert!(!plan_names(&repo, &["--stale-days", "0"]).contains(&"feature/head".to_string()));
+
+    let output = giter(
+        dir.path(),
+        &["branch", "prune", "--stale-days", "0", "--yes"],
+        &[],
+    );
+    assert!(output.status.success());
+    assert!(branch_exists(&repo, "feature/head"));
+    assert!(branch_exists(&repo, "main"));
+}
+
+#[test]
+fn nothing_is_deleted_without_yes() {
+    let (dir, repo) = prepare();
+    let output = giter(dir.path(), &["branch", "prune", "--stale-days", "30"], &[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("使用 --yes 执行删除"));
+    for name in ["feature/merged", "feature/gone", "feature/stale"] {
+        assert!(branch_exists(&repo, name), "{}", name);
+    }
+
+    let output = giter(
+        dir.path(),
+        &["branch", "prune", "--stale-days", "30", "--yes"],
+        &[],
+    );
+    assert!(output.status.success());
+    for name in ["feature/merged", "feature/gone", "feature/stale"] {
+        assert!(!branch_exists(&repo, name), "{}", name);
+    }
+    for name in ["main", "feature/active", "feature/head"] {
+        assert!(branch_exists(&repo, name), "{}", name);
+    }
+}
+
+#[test]
+fn reasons_share_one_schema_across_formats() {
+    let (dir, _repo) = prepare();
+    let args = ["branch", "prune", "--stale-days", "30"];
+
+    let plan = giter_json(dir.path(), &args);
+    let stale = plan
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|candidate| candidate["branch"]["name"] == "feature/stale")
+        .unwrap();
+    assert_eq!(stale["reasons"], serde_json::json!(["stale:100"]));
+
+    let mut all = vec!["--format", "csv"];
+    all.extend(args);
+    let output = giter(dir.path(), &all, &[]);
+    assert!(output.status.success());
+    let csv = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        csv.lines()
+            .any(|line| line.starts_with("feature/stale,stale:100,")),
+        "{}",
+        csv
+    );
+
+    let mut all = vec!["--format", "yaml"];
+    all.extend(args);
+    let output = giter(dir.path(), &all, &[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("- stale:100"));
+}
+
+#[test]
+fn local_base_branch_without_upstream_is_kept() {
+    let (dir, repo) = prepare();
+    // 远程默认分支为 origin/main，本地 main 推送时没有设置上游
+    let main = repo.refname_to_id("refs/heads/main").unwrap();
+    repo.reference("refs/remotes/origin/main", main, false, "")
+        .unwrap();
+    repo.reference_symbolic(
+        "refs/remotes/origin/HEAD",
+        "refs/remotes/origin/main",
+        false,
+        "",
+    )
+    .unwrap();
+
+    assert_eq!(plan_names(&repo, &[]), ["feature/gone", "feature/merged"]);
+    assert!(!plan_names(&repo, &["--base", "origin/main"]).contains(&"main".to_string()));
+
+    let output = giter(dir.path(), &["branch", "prune", "--yes"], &[]);
+    assert!(output.status.success());
+    assert!(branch_exists(&repo, "main"));
+}
@@ -0,0 +1,133 @@
+//! `giter switch` 通过终端界面选择分支，这里直接测试切换分支的函数
+
+// 只用到被引入模块中的一部分
+#![allow(dead_code)]
+
+mod common;
+
+#[path = "../src/models/commit.rs"]
+mod commit;
+#[path = "../src/commands/switch.rs"]
+mod switch;
+
+mod models {
+    pub use super::commit::CommitInfo;
+}
+
+use std::fs;
+
+use git2::build::CheckoutBuilder;
+use git2::{BranchType, Oid, Repository};
+use tempfile::TempDir;
+
+use common::{commit, commit_on, init_repo};
+use switch::{checkout_branch, is_worktree_dirty, stash_changes};
+
+/// 创建工作区与 HEAD 一致的仓库，返回 main 上的提交
+///
+/// main 上有 `main.txt`，`orphan` 分支是只包含 `orphan.txt` 的根提交。
+fn prepare() -> (TempDir, Repository, Oid) {
+    let (dir, repo) = init_repo();
+    let head = commit(&repo, "first", 1_700_000_000);
+    commit_on(&repo, "orphan", None, "orphan", 1_700_000_100);
+    repo.checkout_head(Some(CheckoutBuilder::new().force()))
+        .unwrap();
+
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Tester").unwrap();
+    config.set_str("user.email", "tester@example.com").unwrap();
+    (dir, repo, head)
+}
+
+fn head_name(repo: &Repository) -> String {
+    repo.head().unwrap().shorthand().unwrap().to_string()
+}
+
+#[test]
+fn remote_branch_creates_tracking_branch() {
+    let (dir, repo, head) = prepare();
+    repo.remote("origin", "https://example.invalid/repo.git")
+        .unwrap();
+    let feature = commit_on(&repo, "feature", Some(head), "feature", 1_700_000_200);
+    repo.reference("refs/remotes/origin/feature", feature, false, "")
+        .unwrap();
+    repo.find_branch("feature", BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    let name = checkout_branch(&repo, "origin/feature", true, false).unwrap();
+    assert_eq!(name, "feature");
+    assert_eq!(head_name(&repo), "feature");
+    let branch = repo.find_branch("feature", BranchType::Local).unwrap();
+    assert_eq!(branch.get().target(), Some(feature));
+    assert_eq!(
+        branch.upstream().unwrap().name().unwrap(),
+        Some("origin/feature")
+    );
+    assert!(dir.path().join("feature.txt").exists());
+
+    // 本地分支已存在时直接切换，不会重新创建
+    checkout_branch(&repo, "main", false, false).unwrap();
+    commit_on(&repo, "feature", None, "local only", 1_700_000_300);
+    checkout_branch(&repo, "origin/feature", true, false).unwrap();
+    assert_ne!(repo.head().unwrap().target(), Some(feature));
+}
+
+#[test]
+fn untracked_files_do_not_make_worktree_dirty() {
+    let (dir, repo, _) = prepare();
+    assert!(!is_worktree_dirty(&repo).unwrap());
+
+    fs::write(dir.path().join("notes.txt"), "untracked").unwrap();
+    assert!(!is_worktree_dirty(&repo).unwrap());
+
+    fs::write(dir.path().join("main.txt"), "changed").unwrap();
+    assert!(is_worktree_dirty(&repo).unwrap());
+}
+
+#[test]
+fn conflicting_changes_block_switching() {
+    let (dir, repo, _) = prepare();
+    fs::write(dir.path().join("main.txt"), "changed").unwrap();
+
+    let error = checkout_branch(&repo, "orphan", false, false).unwrap_err();
+    assert!(format!("{:#}", error).contains("--stash"));
+    assert_eq!(head_name(&repo), "main");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("main.txt")).unwrap(),
+        "changed"
+    );
+}
+
+#[test]
+fn stash_keeps_changes_before_switching() {
+    let (dir, mut repo, _) = prepare();
+    fs::write(dir.path().join("main.txt"), "changed").unwrap();
+
+    stash_changes(&mut repo).unwrap();
+    assert!(!is_worktree_dirty(&repo).unwrap());
+    checkout_branch(&repo, "orphan", false, false).unwrap();
+    assert_eq!(head_name(&repo), "orphan");
+
+    let mut stashes = Vec::new();
+    repo.stash_foreach(|_, message, _| {
+        stashes.push(message.to_string());
+        true
+    })
+    .unwrap();
+    assert_eq!(stashes.len(), 1);
+    assert!(stashes[0].contains("giter switch"));
+}
+
+#[test]
+fn force_discards_conflicting_changes() {
+    let (dir, repo, _) = prepare();
+    fs::write(dir.path().join("main.txt"), "changed").unwrap();
+
+    checkout_branch(&repo, "orphan", false, true).unwrap();
+    assert_eq!(head_name(&repo), "orphan");
+    assert!(!dir.path().join("main.txt").exists());
+    assert!(dir.path().join("orphan.txt").exists());
+    assert!(!is_worktree_dirty(&repo).unwrap());
+}
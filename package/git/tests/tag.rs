@@ -0,0 +1,191 @@
+mod common;
+
+use git2::{Oid, Repository};
+use serde_json::Value;
+use tempfile::TempDir;
+
+use common::{commit, giter, giter_json, init_bare, init_repo};
+
+fn tag(repo: &Repository, name: &str, oid: Oid, force: bool) {
+    let object = repo.find_object(oid, None).unwrap();
+    repo.tag_lightweight(name, &object, force).unwrap();
+}
+
+/// 添加空的裸仓库作为远程仓库
+fn add_remote(repo: &Repository, name: &str) -> TempDir {
+    let remote = init_bare();
+    repo.remote(name, remote.path().to_str().unwrap()).unwrap();
+    remote
+}
+
+/// 按名称查找标签的同步状态
+fn status<'a>(tags: &'a Value, name: &str) -> &'a str {
+    tags.as_array()
+        .unwrap()
+        .iter()
+        .find(|tag| tag["name"] == name)
+        .unwrap_or_else(|| panic!("没有找到标签 {}: {}", name, tags))["status"]
+        .as_str()
+        .unwrap()
+}
+
+#[test]
+fn compares_local_and_remote_tags() {
+    let (dir, repo) = init_repo();
+    let first = commit(&repo, "first", 1_700_000_000);
+    let second = commit(&repo, "second", 1_700_000_100);
+    tag(&repo, "v0.9.0", first, false);
+    tag(&repo, "v1.0.0", first, false);
+    tag(&repo, "v1.1.0", second, false);
+    let _remote = add_remote(&repo, "origin");
+    let output = giter(dir.path(), &["tag", "push", "--all"], &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    tag(&repo, "v1.1.0", first, true);
+    tag(&repo, "v2.0.0", second, false);
+    repo.tag_delete("v0.9.0").unwrap();
+
+    let listing = giter_json(dir.path(), &["tag"]);
+    let local = &listing["local"];
+    assert_eq!(local.as_array().unwrap().len(), 3);
+    assert_eq!(status(local, "v1.0.0"), "synced");
+    assert_eq!(status(local, "v1.1.0"), "diverged");
+    assert_eq!(status(local, "v2.0.0"), "local_only");
+
+    let remote = &listing["remote"];
+    assert_eq!(remote.as_array().unwrap().len(), 3);
+    assert_eq!(status(remote, "v0.9.0"), "remote_only");
+    assert_eq!(status(remote, "v1.0.0"), "synced");
+    assert_eq!(status(remote, "v1.1.0"), "diverged");
+    assert!(remote
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|tag| tag["remote"] == "origin"));
+}
+
+#[test]
+fn empty_remote_has_no_tags() {
+    let (dir, repo) = init_repo();
+    let head = commit(&repo, "first", 1_700_000_000);
+    tag(&repo, "v1.0.0", head, false);
+    let _remote = add_remote(&repo, "origin");
+
+    let output = giter(dir.path(), &["--format", "json", "tag"], &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("警告"));
+    let listing: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["remote"], serde_json::json!([]));
+    assert_eq!(status(&listing["local"], "v1.0.0"), "local_only");
+}
+
+#[test]
+fn local_listing_does_not_contact_remotes() {
+    let (dir, repo) = init_repo();
+    // 无法连接的远程仓库
+    let missing = dir.path().join("missing.git");
+    repo.remote("origin", missing.to_str().unwrap()).unwrap();
+    let head = commit(&repo, "first", 1_700_000_000);
+    tag(&repo, "v1.0.0", head, false);
+
+    let output = giter(dir.path(), &["--format", "json", "tag", "--local"], &[]);
+    assert!(output.status.success());
+    assert!(
+        !String::from_utf8_lossy(&output.stderr).contains("警告"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let listing: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status(&listing["local"], "v1.0.0"), "unchecked");
+
+    // 不加 --local 时会尝试连接远程仓库并给出警告
+    let output = giter(dir.path(), &["--format", "json", "tag"], &[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("警告"));
+}
+
+#[test]
+fn deletes_remote_tags() {
+    let (dir, repo) = init_repo();
+    let head = commit(&repo, "first", 1_700_000_000);
+    let origin = add_remote(&repo, "origin");
+    let upstream = add_remote(&repo, "upstream");
+    tag(&repo, "v1.2.0", head, false);
+    for remote in ["origin", "upstream"] {
+        let output = giter(dir.path(), &["tag", "push", "--all", "-r", remote], &[]);
+        assert!(output.status.success());
+    }
+    let remote_has_tag = |remote: &TempDir| {
+        Repository::open_bare(remote.path())
+            .unwrap()
+            .find_reference("refs/tags/v1.2.0")
+            .is_ok()
+    };
+
+    // -r 之后的参数是标签名，而不是远程仓库名
+    let output = giter(dir.path(), &["tag", "delete", "-r", "v1.2.0"], &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!remote_has_tag(&origin));
+    assert!(remote_has_tag(&upstream));
+    assert!(repo.find_reference("refs/tags/v1.2.0").is_err());
+
+    let output = giter(
+        dir.path(),
+        &["tag", "delete", "v1.2.0", "--remote-name", "upstream"],
+        &[],
+    );
+    assert!(!output.status.success());
+
+    let output = giter(
+        dir.path(),
+        &[
+            "tag",
+            "delete",
+            "v1.2.0",
+            "--remote",
+            "--remote-name",
+            "upstream",
+        ],
+        &[],
+    );
+    assert!(output.status.success());
+    assert!(!remote_has_tag(&upstream));
+}
+
+#[test]
+fn pushes_first_tag_to_empty_remote() {
+    let (dir, repo) = init_repo();
+    let head = commit(&repo, "first", 1_700_000_000);
+    tag(&repo, "v1.0.0", head, false);
+    let remote = add_remote(&repo, "origin");
+
+    let output = giter(dir.path(), &["tag", "push", "v1.0.0"], &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("已推送 1 个标签到 origin: v1.0.0"));
+    let pushed = Repository::open_bare(remote.path())
+        .unwrap()
+        .refname_to_id("refs/tags/v1.0.0")
+        .unwrap();
+    assert_eq!(pushed, head);
+
+    // 远程只有标签、没有分支时同样可以比较
+    let output = giter(dir.path(), &["tag", "push", "v1.0.0"], &[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("中的标签已是最新"));
+}
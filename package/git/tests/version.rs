@@ -0,0 +1,155 @@
+mod common;
+
+use std::path::Path;
+
+use git2::Repository;
+use tempfile::TempDir;
+
+use common::{commit, giter, giter_json, init_repo};
+
+/// 创建带有指定轻量标签的仓库
+fn repo_with_tags(tags: &[&str]) -> (TempDir, Repository) {
+    let (dir, repo) = init_repo();
+    let head = commit(&repo, "first", 1_700_000_000);
+    let object = repo.find_object(head, None).unwrap();
+    for tag in tags {
+        repo.tag_lightweight(tag, &object, false).unwrap();
+    }
+    drop(object);
+    (dir, repo)
+}
+
+/// 运行命令并返回去掉首尾空白的标准输出
+fn stdout_line(repo: &Path, args: &[&str]) -> String {
+    let output = giter(repo, args, &[]);
+    assert!(
+        output.status.success(),
+        "{:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// 基于唯一的标签 `tag` 计算下一个版本
+fn bump(tag: &str, args: &[&str]) -> String {
+    let (dir, _repo) = repo_with_tags(&[tag]);
+    let mut all = vec!["tag", "bump"];
+    all.extend(args);
+    all.push("--dry-run");
+    stdout_line(dir.path(), &all)
+}
+
+#[test]
+fn parses_prefixed_versions() {
+    let (dir, _repo) = repo_with_tags(&[
+        "v1.2.3",
+        "v1.10.0-rc.1",
+        "v1.9.0",
+        "ziper-v0.1.1",
+        "ziper-v0.1.0",
+        "release-2024",
+    ]);
+    let path = dir.path();
+
+    assert_eq!(
+        stdout_line(path, &["tag", "latest", "--prefix", "v"]),
+        "v1.9.0"
+    );
+    assert_eq!(
+        stdout_line(path, &["tag", "latest", "--prefix", "v", "--pre"]),
+        "v1.10.0-rc.1"
+    );
+    assert_eq!(
+        stdout_line(path, &["tag", "latest", "--prefix", "ziper-v"]),
+        "ziper-v0.1.1"
+    );
+
+    // 存在多种前缀时需要指定 --prefix
+    let output = giter(path, &["tag", "latest"], &[]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn bumps_release_versions() {
+    assert_eq!(bump("v1.2.3", &["major"]), "v2.0.0");
+    assert_eq!(bump("v1.2.3", &["minor"]), "v1.3.0");
+    assert_eq!(bump("v1.2.3", &["patch"]), "v1.2.4");
+    assert_eq!(bump("v1.2.3", &["prerelease"]), "v1.2.4-0");
+    assert_eq!(
+        bump("v1.2.3", &["prerelease", "--preid", "rc"]),
+        "v1.2.4-rc.0"
+    );
+    assert_eq!(bump("ziper-v0.1.1", &["patch"]), "ziper-v0.1.2");
+    assert_eq!(bump("v1.2.3+build.5", &["patch"]), "v1.2.4");
+}
+
+#[test]
+fn bumps_prerelease_versions() {
+    // 对应的正式版本尚未发布时直接去掉预发布标识
+    assert_eq!(bump("v1.3.0-rc.1", &["patch"]), "v1.3.0");
+    assert_eq!(bump("v1.3.0-rc.1", &["minor"]), "v1.3.0");
+    assert_eq!(bump("v1.3.0-rc.1", &["major"]), "v2.0.0");
+    assert_eq!(bump("v2.0.0-rc.1", &["major"]), "v2.0.0");
+    assert_eq!(bump("v1.3.1-rc.1", &["minor"]), "v1.4.0");
+
+    assert_eq!(bump("v1.3.0-rc.1", &["prerelease"]), "v1.3.0-rc.2");
+    assert_eq!(bump("v1.3.0-rc", &["prerelease"]), "v1.3.0-rc.0");
+    assert_eq!(bump("v1.3.0-4", &["prerelease"]), "v1.3.0-5");
+    assert_eq!(
+        bump("v1.3.0-rc.1", &["prerelease", "--preid", "rc"]),
+        "v1.3.0-rc.2"
+    );
+    assert_eq!(
+        bump("v1.3.0-beta.2", &["prerelease", "--preid", "rc"]),
+        "v1.3.0-rc.0"
+    );
+}
+
+#[test]
+fn bumps_from_zero_without_tags() {
+    let (dir, _repo) = repo_with_tags(&[]);
+    assert_eq!(
+        stdout_line(
+            dir.path(),
+            &["tag", "bump", "minor", "--prefix", "v", "--dry-run"]
+        ),
+        "v0.1.0"
+    );
+}
+
+#[test]
+fn sorts_tags_by_version() {
+    let (dir, _repo) = repo_with_tags(&[
+        "v1.10.0",
+        "v1.2.0",
+        "v1.2.0-rc.1",
+        "v1.9.0",
+        "ziper-v0.2.0",
+        "ziper-v0.10.0",
+        "nightly",
+        "a-release",
+    ]);
+
+    let listing = giter_json(dir.path(), &["tag", "--local"]);
+    let names: Vec<_> = listing["local"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag["name"].as_str().unwrap())
+        .collect();
+    // 同一前缀内按语义化版本排序，前缀之间和非版本标签按名称排序
+    assert_eq!(
+        names,
+        [
+            "a-release",
+            "nightly",
+            "v1.2.0-rc.1",
+            "v1.2.0",
+            "v1.9.0",
+            "v1.10.0",
+            "ziper-v0.2.0",
+            "ziper-v0.10.0",
+        ]
+    );
+}
@@ -0,0 +1,649 @@
+use anyhow::{bail, Context, Result};
+use bzip2::write::BzEncoder;
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use std::fs::{self, File, Metadata};
+use std::io::{self, Seek, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tar::{Header, HeaderMode};
+use tempfile::{NamedTempFile, SpooledTempFile};
+use time::OffsetDateTime;
+use xz2::write::XzEncoder;
+use zip::unstable::write::FileOptionsExt;
+use zip::write::{ExtendedFileOptions, FileOptions};
+use zip::{AesMode, CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+use crate::split::{SplitMode, SplitOptions};
+
+/// 压缩包格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    #[value(name = "tar.gz", alias = "tgz")]
+    TarGz,
+    #[value(name = "tar.zst", alias = "tzst")]
+    TarZst,
+    #[value(name = "tar.xz", alias = "txz")]
+    TarXz,
+    #[value(name = "tar.bz2", alias = "tbz2")]
+    TarBz2,
+}
+
+impl ArchiveFormat {
+    /// 根据文件扩展名推断格式，无法识别时返回 None
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        let format = if name.ends_with(".zip") {
+            Self::Zip
+        } else if name.ends_with(".tar") {
+            Self::Tar
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Self::TarGz
+        } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Self::TarZst
+        } else if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+            Self::TarXz
+        } else if name.ends_with(".tar.bz2") || name.ends_with(".tbz2") || name.ends_with(".tbz") {
+            Self::TarBz2
+        } else {
+            return None;
+        };
+        Some(format)
+    }
+
+    /// 默认的文件扩展名（不含前导点）
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Tar => "tar",
+            Self::TarGz => "tar.gz",
+            Self::TarZst => "tar.zst",
+            Self::TarXz => "tar.xz",
+            Self::TarBz2 => "tar.bz2",
+        }
+    }
+    /// 外层压缩支持的级别范围，不压缩的格式返回 None
+    fn level_range(self) -> Option<RangeInclusive<i64>> {
+        match self {
+            Self::Zip | Self::Tar => None,
+            Self::TarGz | Self::TarXz => Some(0..=9),
+            Self::TarZst => Some(1..=22),
+            Self::TarBz2 => Some(1..=9),
+        }
+    }
+}
+
+/// ZIP 条目的压缩方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Method {
+    Store,
+    Deflate,
+    Bzip2,
+    Zstd,
+    /// 基于 LZMA2 的 XZ（方法 95）
+    Xz,
+    /// zip 库无法写入 LZMA（方法 14），只用于给出明确的错误
+    #[value(hide = true)]
+    Lzma,
+}
+
+impl Method {
+    fn compression_method(self) -> CompressionMethod {
+        match self {
+            Self::Store => CompressionMethod::Stored,
+            Self::Deflate => CompressionMethod::Deflated,
+            Self::Bzip2 => CompressionMethod::Bzip2,
+            Self::Zstd => CompressionMethod::Zstd,
+            Self::Xz => CompressionMethod::Xz,
+            Self::Lzma => unreachable!("lzma 已在 WriteOptions::validate 中拒绝"),
+        }
+    }
+
+    fn level_range(self) -> Option<RangeInclusive<i64>> {
+        match self {
+            Self::Store => None,
+            Self::Deflate | Self::Bzip2 => Some(1..=9),
+            Self::Zstd => Some(1..=22),
+            Self::Xz | Self::Lzma => Some(0..=9),
+        }
+    }
+}
+
+/// ZIP 条目的加密方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Encryption {
+    /// WinZip AES-256，需要较新的解压工具
+    Aes256,
+    /// 传统 ZipCrypto，兼容性最好但容易被破解
+    Zipcrypto,
+}
+
+/// 写入压缩包的选项
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// ZIP 条目的压缩方法，默认为 deflate
+    pub method: Option<Method>,
+    /// 压缩级别，默认使用各压缩方法的默认级别
+    pub level: Option<i64>,
+    /// 使用固定的 644/755 权限代替文件的实际权限
+    pub normalize_permissions: bool,
+    /// 跟随符号链接，写入链接指向的内容
+    pub follow_symlinks: bool,
+    /// 可重现模式下修改时间的上限，设置后还会省略额外字段和属主
+    pub reproducible: Option<SystemTime>,
+    /// 文件条目的加密方式，只适用于 ZIP 格式
+    pub encryption: Option<Encryption>,
+    /// 加密使用的密码
+    pub password: String,
+    /// 按大小拆分为多个分卷
+    pub split: Option<SplitOptions>,
+    /// 写入完成后重新读取并校验压缩包
+    pub verify: bool,
+}
+
+/// 已压缩的文件类型，再次压缩几乎没有收益
+const COMPRESSED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "heic", "zip", "gz", "tgz", "bz2", "xz", "zst",
+    "7z", "rar", "jar", "woff", "woff2", "mp3", "mp4", "m4a", "mov", "mkv", "webm",
+];
+
+fn is_compressed(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| COMPRESSED_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 条目的 Unix 权限，规范化时目录和可执行文件为 755，其余为 644
+fn entry_mode(metadata: &Metadata, normalize: bool) -> u32 {
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o777
+    };
+    #[cfg(not(unix))]
+    let mode = if metadata.is_dir() {
+        0o755
+    } else if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    };
+
+    if !normalize {
+        mode
+    } else if metadata.is_dir() || mode & 0o111 != 0 {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+/// 可重现模式下的修改时间，晚于上限或无法读取时使用上限
+fn clamp_mtime(mtime: Option<SystemTime>, limit: SystemTime) -> SystemTime {
+    mtime.map_or(limit, |mtime| mtime.min(limit))
+}
+
+/// 扩展时间戳（0x5455）额外字段，记录精确到秒的 UTC 修改时间
+///
+/// DOS 时间只精确到 2 秒且没有时区，解压时优先使用该字段。
+fn extended_timestamp(mtime: SystemTime) -> Option<Box<[u8]>> {
+    let seconds = mtime.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let seconds = u32::try_from(seconds).ok()?;
+    let mut data = vec![0x01];
+    data.extend_from_slice(&seconds.to_le_bytes());
+    Some(data.into_boxed_slice())
+}
+
+impl WriteOptions {
+    /// 检查压缩方法、级别和加密方式是否适用于该格式
+    pub fn validate(&self, format: ArchiveFormat) -> Result<()> {
+        if self.encryption.is_some() {
+            if format != ArchiveFormat::Zip {
+                bail!("--encrypt 仅适用于 ZIP 格式");
+            }
+            if self.reproducible.is_some() {
+                bail!("--encrypt 每次使用随机的盐值，不能与 --reproducible 同时使用");
+            }
+        }
+        if let Some(split) = self.split {
+            if split.mode == SplitMode::Spanned && format != ArchiveFormat::Zip {
+                bail!("spanned 分卷仅适用于 ZIP 格式，其他格式请使用 --split-mode numbered");
+            }
+        }
+
+        if self.method == Some(Method::Lzma) {
+            bail!("不支持 lzma 压缩方法：zip 库无法写入 LZMA（方法 14）条目，可以使用 --method xz");
+        }
+
+        let (range, name) = if format == ArchiveFormat::Zip {
+            let method = self.method.unwrap_or(Method::Deflate);
+            let name = method.to_possible_value().unwrap().get_name().to_string();
+            (method.level_range(), name)
+        } else {
+            if self.method.is_some() {
+                bail!(
+                    "--method 仅适用于 ZIP 格式，{} 格式请使用 --level",
+                    format.extension()
+                );
+            }
+            (format.level_range(), format.extension().to_string())
+        };
+
+        match (self.level, range) {
+            (Some(_), None) => bail!("{} 不支持设置压缩级别", name),
+            (Some(level), Some(range)) if !range.contains(&level) => bail!(
+                "{} 的压缩级别必须在 {} 到 {} 之间: {}",
+                name,
+                range.start(),
+                range.end(),
+                level
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 向压缩包写入条目，`name` 为条目在压缩包中的相对路径
+pub trait ArchiveWriter {
+    /// 返回文件编码器，可以在多个线程中预先压缩文件
+    fn encoder(&self) -> FileEncoder;
+
+    /// 写入由 [`FileEncoder::encode`] 编码的文件
+    fn add_file(&mut self, file: EncodedFile) -> Result<()>;
+
+    fn add_directory(&mut self, name: &str, path: &Path) -> Result<()>;
+
+    fn add_symlink(&mut self, name: &str, path: &Path) -> Result<()>;
+
+    /// 写入结尾数据并刷新到文件
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+/// 超过该大小的压缩结果写入临时文件，避免占用过多内存
+const SPOOL_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// 达到该大小的文件使用 ZIP64 格式记录大小
+///
+/// 无法压缩的数据压缩或加密后会略大于原始大小，因此在 4 GiB 之前留出余量。
+const LARGE_FILE_THRESHOLD: u64 = 0xF000_0000;
+
+/// 文件头偏移达到该值时需要记录在 ZIP64 额外字段中
+const ZIP64_OFFSET_LIMIT: u64 = 0xFFFF_FFFF;
+
+/// 写入压缩包之前对文件的预处理
+#[derive(Clone)]
+pub struct FileEncoder {
+    /// ZIP 条目的选项，tar 格式为 None
+    zip: Option<ZipEntryOptions>,
+}
+
+/// 编码后的文件
+pub struct EncodedFile(Encoded);
+
+enum Encoded {
+    /// 只包含该文件一个条目的 ZIP 数据，需要 ZIP64 偏移时从 `path` 重新压缩
+    Zip {
+        data: SpooledTempFile,
+        name: String,
+        path: PathBuf,
+    },
+    /// 写入时再读取的文件
+    Path { name: String, path: PathBuf },
+}
+
+impl FileEncoder {
+    /// ZIP 格式下将文件单独压缩为一个只含该条目的 ZIP，写入时使用 `merge_archive` 复制压缩后的数据
+    pub fn encode(&self, name: &str, path: &Path) -> Result<EncodedFile> {
+        let options = match &self.zip {
+            // 大文件需要 ZIP64 额外字段，由写入器直接压缩
+            Some(options) if fs::metadata(path)?.len() < LARGE_FILE_THRESHOLD => options,
+            _ => {
+                return Ok(EncodedFile(Encoded::Path {
+                    name: name.to_string(),
+                    path: path.to_path_buf(),
+                }))
+            }
+        };
+
+        let mut zip = ZipWriter::new(SpooledTempFile::new(SPOOL_THRESHOLD));
+        options.write_file(&mut zip, name, path)?;
+        Ok(EncodedFile(Encoded::Zip {
+            data: zip.finish()?,
+            name: name.to_string(),
+            path: path.to_path_buf(),
+        }))
+    }
+}
+
+/// 在输出文件所在目录创建临时文件，写入完成后再重命名为目标文件名
+pub fn temp_file_for(output: &Path) -> io::Result<NamedTempFile> {
+    let dir = match output.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".ziper-").suffix(".tmp");
+    #[cfg(unix)]
+    {
+        // 与 File::create 相同的默认权限，实际权限还会受 umask 影响
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    builder.tempfile_in(dir)
+}
+
+/// 按格式创建写入器，选项需先经过 [`WriteOptions::validate`] 检查
+pub fn new_writer(
+    format: ArchiveFormat,
+    file: File,
+    options: &WriteOptions,
+) -> Result<Box<dyn ArchiveWriter>> {
+    let level = options.level;
+    let compressor = match format {
+        ArchiveFormat::Zip => {
+            let method = options.method.unwrap_or(Method::Deflate);
+            return Ok(Box::new(ZipArchiveWriter {
+                file: file.try_clone()?,
+                zip: ZipWriter::new(file),
+                zip64: false,
+                options: ZipEntryOptions {
+                    method: method.compression_method(),
+                    level,
+                    normalize_permissions: options.normalize_permissions,
+                    reproducible: options.reproducible,
+                    encryption: options.encryption,
+                    password: options.password.clone(),
+                },
+            }));
+        }
+        ArchiveFormat::Tar => Compressor::Plain(file),
+        ArchiveFormat::TarGz => Compressor::Gzip(GzEncoder::new(
+            file,
+            level.map_or_else(Default::default, |l| flate2::Compression::new(l as u32)),
+        )),
+        ArchiveFormat::TarZst => Compressor::Zstd(
+            zstd::Encoder::new(
+                file,
+                level.map_or(zstd::DEFAULT_COMPRESSION_LEVEL, |l| l as i32),
+            )
+            .context("无法初始化 zstd 压缩")?,
+        ),
+        ArchiveFormat::TarXz => Compressor::Xz(XzEncoder::new(file, level.unwrap_or(6) as u32)),
+        ArchiveFormat::TarBz2 => Compressor::Bzip2(BzEncoder::new(
+            file,
+            level.map_or_else(Default::default, |l| bzip2::Compression::new(l as u32)),
+        )),
+    };
+    Ok(Box::new(TarArchiveWriter::new(compressor, options)))
+}
+
+#[derive(Clone)]
+struct ZipEntryOptions {
+    method: CompressionMethod,
+    level: Option<i64>,
+    normalize_permissions: bool,
+    reproducible: Option<SystemTime>,
+    /// 只加密文件内容，目录和符号链接不加密
+    encryption: Option<Encryption>,
+    password: String,
+}
+
+impl ZipEntryOptions {
+    /// 根据元数据设置权限和修改时间
+    fn entry_options(
+        &self,
+        metadata: &Metadata,
+    ) -> Result<FileOptions<'static, ExtendedFileOptions>> {
+        let mode = if metadata.is_symlink() {
+            0o777
+        } else {
+            entry_mode(metadata, self.normalize_permissions)
+        };
+        let mut options = FileOptions::default().unix_permissions(mode);
+
+        let mtime = match self.reproducible {
+            Some(limit) => Some(clamp_mtime(metadata.modified().ok(), limit)),
+            None => metadata.modified().ok(),
+        };
+        if let Some(mtime) = mtime {
+            // 与解压时一致，DOS 时间按 UTC 记录，早于 1980 年时使用 1980-01-01
+            let datetime = DateTime::try_from(OffsetDateTime::from(mtime)).unwrap_or_default();
+            options = options.last_modified_time(datetime);
+            // 可重现模式下只保留 DOS 时间，不写入额外字段
+            if let (None, Some(data)) = (self.reproducible, extended_timestamp(mtime)) {
+                options.add_extra_data(0x5455, data, false)?;
+            }
+        }
+        Ok(options)
+    }
+
+    /// 压缩文件并写入一个条目，已压缩的文件类型直接存储
+    fn write_file<W: Write + Seek>(
+        &self,
+        zip: &mut ZipWriter<W>,
+        name: &str,
+        path: &Path,
+    ) -> Result<()> {
+        let mut f = File::open(path)?;
+        let metadata = f.metadata()?;
+        let options = self
+            .entry_options(&metadata)?
+            .large_file(metadata.len() >= LARGE_FILE_THRESHOLD);
+        let options = if is_compressed(path) {
+            options.compression_method(CompressionMethod::Stored)
+        } else {
+            options
+                .compression_method(self.method)
+                .compression_level(self.level)
+        };
+        let options = match self.encryption {
+            Some(Encryption::Aes256) => {
+                options.with_aes_encryption(AesMode::Aes256, &self.password)
+            }
+            Some(Encryption::Zipcrypto) => {
+                options.with_deprecated_encryption(self.password.as_bytes())
+            }
+            None => options,
+        };
+
+        zip.start_file(name, options)?;
+        io::copy(&mut f, zip)?;
+        Ok(())
+    }
+}
+
+struct ZipArchiveWriter {
+    zip: ZipWriter<File>,
+    /// 与 `zip` 写入同一文件，用于获取已写入的大小
+    file: File,
+    /// 已写入大文件或超过 4 GiB，之后的条目都由 zip 库直接写入以记录 ZIP64 偏移
+    zip64: bool,
+    options: ZipEntryOptions,
+}
+
+impl ArchiveWriter for ZipArchiveWriter {
+    fn encoder(&self) -> FileEncoder {
+        FileEncoder {
+            zip: Some(self.options.clone()),
+        }
+    }
+
+    /// 复制已压缩的条目，`raw_copy_file` 会丢失扩展时间戳和 AES 等额外字段，因此使用 `merge_archive`
+    ///
+    /// `merge_archive` 不会写入 ZIP64 额外字段，文件头偏移超出 4 GiB 后改为重新压缩该文件。
+    fn add_file(&mut self, file: EncodedFile) -> Result<()> {
+        let (name, path) = match file.0 {
+            Encoded::Zip {
+                mut data,
+                name,
+                path,
+            } => {
+                if !self.zip64 && self.file.metadata()?.len() < ZIP64_OFFSET_LIMIT {
+                    data.rewind()?;
+                    self.zip.merge_archive(ZipArchive::new(data)?)?;
+                    return Ok(());
+                }
+                (name, path)
+            }
+            Encoded::Path { name, path } => (name, path),
+        };
+        // 压缩中的数据尚未全部写入文件，无法得知之后的偏移
+        self.zip64 = true;
+        self.options.write_file(&mut self.zip, &name, &path)
+    }
+
+    fn add_directory(&mut self, name: &str, path: &Path) -> Result<()> {
+        let options = self.options.entry_options(&fs::metadata(path)?)?;
+        self.zip.add_directory(name, options)?;
+        Ok(())
+    }
+
+    fn add_symlink(&mut self, name: &str, path: &Path) -> Result<()> {
+        let target = fs::read_link(path)?;
+        let options = self.options.entry_options(&fs::symlink_metadata(path)?)?;
+        self.zip
+            .add_symlink(name, target.to_string_lossy(), options)?;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        self.zip.finish()?;
+        Ok(())
+    }
+}
+
+/// tar 数据流外层的压缩
+enum Compressor {
+    Plain(File),
+    Gzip(GzEncoder<File>),
+    Zstd(zstd::Encoder<'static, File>),
+    Xz(XzEncoder<File>),
+    Bzip2(BzEncoder<File>),
+}
+
+impl Compressor {
+    fn finish(self) -> io::Result<File> {
+        match self {
+            Self::Plain(file) => Ok(file),
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Zstd(encoder) => encoder.finish(),
+            Self::Xz(encoder) => encoder.finish(),
+            Self::Bzip2(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl Write for Compressor {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+            Self::Xz(encoder) => encoder.write(buf),
+            Self::Bzip2(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+            Self::Xz(encoder) => encoder.flush(),
+            Self::Bzip2(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// tar 写入器，保留 Unix 权限、修改时间、属主和符号链接
+struct TarArchiveWriter {
+    builder: tar::Builder<Compressor>,
+    normalize_permissions: bool,
+    follow_symlinks: bool,
+    reproducible: Option<SystemTime>,
+}
+
+impl TarArchiveWriter {
+    fn new(compressor: Compressor, options: &WriteOptions) -> Self {
+        let mut builder = tar::Builder::new(compressor);
+        builder.mode(HeaderMode::Complete);
+        builder.follow_symlinks(options.follow_symlinks);
+        Self {
+            builder,
+            normalize_permissions: options.normalize_permissions,
+            follow_symlinks: options.follow_symlinks,
+            reproducible: options.reproducible,
+        }
+    }
+
+    fn append(&mut self, name: &str, path: &Path) -> Result<()> {
+        self.append_entry(name, path)
+            .with_context(|| format!("无法写入: {}", path.display()))
+    }
+
+    fn append_entry(&mut self, name: &str, path: &Path) -> io::Result<()> {
+        let metadata = if self.follow_symlinks {
+            fs::metadata(path)?
+        } else {
+            fs::symlink_metadata(path)?
+        };
+        if !self.normalize_permissions && self.reproducible.is_none() {
+            return self.builder.append_path_with_name(path, name);
+        }
+
+        let mut header = Header::new_gnu();
+        if let Some(limit) = self.reproducible {
+            // 属主为 0，修改时间不晚于上限
+            header.set_metadata_in_mode(&metadata, HeaderMode::Deterministic);
+            let mtime = clamp_mtime(metadata.modified().ok(), limit);
+            header.set_mtime(mtime.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()));
+        } else {
+            header.set_metadata_in_mode(&metadata, HeaderMode::Complete);
+        }
+
+        if metadata.is_symlink() {
+            header.set_mode(0o777);
+            return self
+                .builder
+                .append_link(&mut header, name, fs::read_link(path)?);
+        }
+
+        header.set_mode(entry_mode(&metadata, true));
+        if metadata.is_dir() {
+            self.builder.append_data(&mut header, name, io::empty())
+        } else {
+            self.builder
+                .append_data(&mut header, name, File::open(path)?)
+        }
+    }
+}
+
+impl ArchiveWriter for TarArchiveWriter {
+    fn encoder(&self) -> FileEncoder {
+        FileEncoder { zip: None }
+    }
+
+    fn add_file(&mut self, file: EncodedFile) -> Result<()> {
+        let Encoded::Path { name, path } = file.0 else {
+            bail!("tar 写入器不能写入 ZIP 条目");
+        };
+        self.append(&name, &path)
+    }
+
+    fn add_directory(&mut self, name: &str, path: &Path) -> Result<()> {
+        self.append(name, path)
+    }
+
+    fn add_symlink(&mut self, name: &str, path: &Path) -> Result<()> {
+        self.append(name, path)
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        let compressor = self.builder.into_inner()?;
+        compressor.finish()?.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,364 @@
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand, ValueEnum};
+use std::ffi::OsString;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use crate::archive::{ArchiveFormat, Encryption, Method};
+use crate::split::{self, SplitMode};
+
+/// 快速的文件压缩工具
+///
+/// 一个简单易用的压缩工具，用于将文件或目录压缩为ZIP或tar格式。
+/// 支持指定输出文件名和路径，忽略特定文件或目录，以及不同的输出详细级别。
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(after_help = "示例:
+
+```
+# 基本用法：压缩目录为 [目录名].zip
+ziper dist
+
+# 指定输出文件
+ziper dist output.zip
+
+# 指定输出目录和文件名
+ziper dist path/to/output.zip
+
+# 根据扩展名输出 tar.gz、tar.zst、tar.xz 或 tar.bz2
+ziper dist dist.tar.gz
+
+# 使用 zstd 最高级别压缩
+ziper dist --method zstd --level 22
+
+# 忽略特定模式
+ziper dist --ignore \"node_modules,.git,*.zip\"
+
+# 解压到指定目录
+ziper extract dist.zip out
+
+# 查看压缩包内容
+ziper list dist.zip
+
+# 检查压缩包是否损坏
+ziper test dist.zip
+
+# 拆分为不超过 500M 的分卷
+ziper dist --split-size 500M
+
+# 使用 AES-256 加密，密码从文件读取
+ziper dist --encrypt aes256 --password-file secret.txt
+
+# 源目录与子命令同名时，无法按子命令解析则压缩该目录，也可以写成 ./extract
+ziper extract
+
+# 使用静默模式
+ziper dist -q
+
+# 使用详细模式
+ziper dist -v
+```")]
+pub struct Cli {
+    /// 子命令，不指定时压缩 SOURCE
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// 要压缩的源文件或目录
+    ///
+    /// 指定需要被压缩的文件或目录的路径。
+    /// 如果是目录，将递归压缩其中的所有内容。
+    pub source: Option<String>,
+
+    /// 输出的压缩文件路径
+    ///
+    /// 指定生成的压缩文件的路径和名称，格式根据扩展名推断。
+    /// 如果不提供，默认使用源名称加格式对应的后缀（默认为.zip）。
+    pub output: Option<String>,
+
+    /// 压缩包格式
+    ///
+    /// 强制使用指定的格式，忽略输出文件的扩展名。
+    /// tar 格式会保留 Unix 权限、属主和符号链接。
+    #[arg(short = 'f', long = "format", value_enum)]
+    pub format: Option<ArchiveFormat>,
+
+    /// ZIP 条目的压缩方法
+    ///
+    /// 默认为 deflate。png、jpg、zip、gz、woff2、mp4 等已压缩的文件
+    /// 始终直接存储。zip 库不能写入 LZMA 条目，需要时请使用基于 LZMA2 的 xz。
+    #[arg(short = 'm', long = "method", value_enum, help_heading = "压缩选项")]
+    pub method: Option<Method>,
+
+    /// 压缩级别
+    ///
+    /// 取值范围取决于压缩方法：deflate、bzip2 为 1-9，zstd 为 1-22，xz 为 0-9。
+    /// tar 格式作用于外层压缩：tar.gz、tar.xz 为 0-9，tar.zst 为 1-22，tar.bz2 为 1-9。
+    #[arg(
+        short = 'l',
+        long = "level",
+        allow_negative_numbers = true,
+        help_heading = "压缩选项"
+    )]
+    pub level: Option<i64>,
+
+    /// 使用固定权限
+    ///
+    /// 默认保留文件的实际权限。指定后目录和可执行文件使用 755，其余文件使用 644。
+    #[arg(long = "normalize-permissions", help_heading = "压缩选项")]
+    pub normalize_permissions: bool,
+
+    /// 生成可重现的压缩包
+    ///
+    /// 相同的内容总是生成完全相同的压缩包：条目按名称排序，使用固定权限，
+    /// 修改时间不晚于 SOURCE_DATE_EPOCH（未设置时统一为 1980-01-01），
+    /// 不写入扩展时间戳等额外字段，tar 格式的属主为 0。
+    #[arg(long = "reproducible", help_heading = "压缩选项")]
+    pub reproducible: bool,
+
+    /// 并行压缩的线程数
+    ///
+    /// 默认使用所有 CPU 核心。ZIP 格式下文件在多个线程中分别压缩后按固定顺序写入，
+    /// 生成的压缩包与线程数无关。tar 格式只有一个压缩流，不受该选项影响。
+    #[arg(short = 'j', long = "jobs", help_heading = "压缩选项")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// 写入完成后校验压缩包
+    ///
+    /// 重新读取写入的压缩包（包括所有分卷），校验每个条目的数据。
+    /// 校验失败时报错并删除写入的文件，不会留下损坏的压缩包。
+    #[arg(long = "verify", help_heading = "压缩选项")]
+    pub verify: bool,
+
+    /// 跟随符号链接
+    ///
+    /// 默认将符号链接保存为链接本身。指定后写入链接指向的文件或目录的内容，
+    /// 指向上级目录的循环链接会被跳过。
+    #[arg(long = "follow-symlinks", help_heading = "过滤选项")]
+    pub follow_symlinks: bool,
+
+    /// 要忽略的模式，使用逗号分隔
+    ///
+    /// 指定在压缩过程中要忽略的文件或目录模式，按 gitignore 规则匹配相对于源目录的路径。
+    /// 不含 `/` 的模式匹配任意层级的名称，含 `/` 的模式相对源目录锚定，支持 `**`。
+    /// 被忽略的目录不会被遍历。
+    /// 例如："node_modules,.git,*.zip,src/*.test.js"
+    #[arg(
+        short = 'i',
+        long = "ignore",
+        value_delimiter = ',',
+        help_heading = "过滤选项"
+    )]
+    pub ignore_patterns: Option<Vec<String>>,
+
+    /// 只包含匹配的文件，使用逗号分隔
+    ///
+    /// 模式规则与 --ignore 相同，匹配目录时包含目录中的所有文件。
+    /// 与 --ignore 同时使用时，被忽略的路径不会被包含。
+    /// 例如："*.html,assets"
+    #[arg(long = "include", value_delimiter = ',', help_heading = "过滤选项")]
+    pub include_patterns: Option<Vec<String>>,
+
+    /// 不读取 .gitignore 和 .ignore
+    ///
+    /// 默认按 gitignore 规则读取源目录中的 .gitignore、.ignore 和 .zipignore 文件。
+    /// 指定后只使用 .zipignore 和 --ignore 中的模式。
+    #[arg(long = "no-vcs-ignore", help_heading = "过滤选项")]
+    pub no_vcs_ignore: bool,
+
+    /// 按大小拆分为多个分卷，例如 500M、2G
+    ///
+    /// 支持 K、M、G 后缀（1024 进制），最小为 64K。
+    /// 压缩包不超过该大小时不拆分。
+    #[arg(
+        long = "split-size",
+        value_name = "SIZE",
+        value_parser = split::parse_size,
+        help_heading = "分卷选项"
+    )]
+    pub split_size: Option<u64>,
+
+    /// 分卷方式
+    ///
+    /// spanned 为 ZIP 标准分卷（.z01、.z02……最后一卷为 .zip），可以被 7-Zip、WinRAR 等工具直接打开；
+    /// numbered 按字节切分为 .001、.002……，适用于任何格式，合并后即为完整的压缩包。
+    /// ZIP 格式默认为 spanned，其他格式默认为 numbered。
+    #[arg(
+        long = "split-mode",
+        value_enum,
+        requires = "split_size",
+        help_heading = "分卷选项"
+    )]
+    pub split_mode: Option<SplitMode>,
+
+    /// 加密 ZIP 中的文件
+    ///
+    /// 密码依次从 --password-file、ZIPER_PASSWORD 环境变量读取，都未提供时在终端中输入。
+    /// 文件名、大小等条目信息不会被加密。
+    #[arg(long = "encrypt", value_enum, help_heading = "加密选项")]
+    pub encrypt: Option<Encryption>,
+
+    /// 从文件的第一行读取密码
+    ///
+    /// 用于加密压缩，以及解压或查看加密的压缩包。
+    #[arg(
+        long = "password-file",
+        value_name = "FILE",
+        global = true,
+        help_heading = "加密选项"
+    )]
+    pub password_file: Option<PathBuf>,
+
+    /// 静默模式 - 不显示输出
+    ///
+    /// 在压缩过程中不显示任何进度信息，除非发生错误。
+    #[arg(
+        short = 'q',
+        long = "quiet",
+        global = true,
+        conflicts_with = "verbose",
+        help_heading = "输出控制"
+    )]
+    pub quiet: bool,
+
+    /// 详细模式 - 显示详细输出
+    ///
+    /// 在压缩过程中显示详细的进度信息，包括每个被处理的文件。
+    #[arg(
+        short = 'v',
+        long = "verbose",
+        global = true,
+        conflicts_with = "quiet",
+        help_heading = "输出控制"
+    )]
+    pub verbose: bool,
+}
+
+impl Cli {
+    /// 解析命令行参数
+    ///
+    /// 源目录与子命令或其别名同名（例如名为 `extract` 或 `x` 的目录）时，
+    /// 如果按子命令解析失败，则把该参数当作 SOURCE 重新解析。
+    pub fn parse_args() -> Self {
+        let args: Vec<OsString> = std::env::args_os().collect();
+        let error = match Self::try_parse_from(&args) {
+            Ok(cli) => return cli,
+            Err(error) => error,
+        };
+        // --help 和 --version 也以错误的形式返回
+        if !error.use_stderr() {
+            error.exit();
+        }
+
+        let command = Self::command();
+        let names: Vec<&str> = command
+            .get_subcommands()
+            .flat_map(|subcommand| subcommand.get_name_and_visible_aliases())
+            .collect();
+        (1..args.len())
+            .filter(|&i| {
+                args[i]
+                    .to_str()
+                    .is_some_and(|arg| names.contains(&arg) && Path::new(arg).exists())
+            })
+            .find_map(|i| {
+                let mut args = args.clone();
+                args[i] = Path::new(".").join(&args[i]).into_os_string();
+                Self::try_parse_from(args).ok()
+            })
+            .unwrap_or_else(|| error.exit())
+    }
+}
+
+/// 压缩包操作命令
+#[derive(Subcommand)]
+pub enum Commands {
+    /// 解压ZIP文件
+    ///
+    /// 将ZIP文件解压到目标目录，并恢复文件的Unix权限和修改时间。
+    /// 包含 `../`、绝对路径或指向目标目录之外的符号链接的压缩包会被拒绝。
+    /// 目标文件已存在时默认报错，可以通过 `--overwrite`、`--skip` 或 `--rename` 指定处理方式。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 解压到当前目录
+    /// ziper extract dist.zip
+    ///
+    /// # 解压到指定目录，覆盖已存在的文件
+    /// ziper extract dist.zip out --overwrite
+    /// ```
+    #[command(visible_alias = "x")]
+    #[command(group(ArgGroup::new("conflict").args(["overwrite", "skip", "rename"])))]
+    Extract {
+        /// 要解压的ZIP文件
+        archive: String,
+
+        /// 解压的目标目录（默认为当前目录）
+        destination: Option<String>,
+
+        /// 覆盖已存在的文件
+        #[arg(long = "overwrite", help_heading = "冲突处理")]
+        overwrite: bool,
+
+        /// 跳过已存在的文件
+        #[arg(long = "skip", help_heading = "冲突处理")]
+        skip: bool,
+
+        /// 已存在时以新名称解压，例如 `a (1).txt`
+        #[arg(long = "rename", help_heading = "冲突处理")]
+        rename: bool,
+    },
+    /// 查看压缩包内容
+    ///
+    /// 列出每个条目的原始大小、压缩后大小、压缩率、压缩方法、CRC32、权限和修改时间，
+    /// 并在最后显示汇总信息。不会解压任何数据。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// # 以表格形式显示
+    /// ziper list dist.zip
+    ///
+    /// # 以 JSON 格式输出
+    /// ziper list dist.zip --format json
+    /// ```
+    #[command(visible_alias = "l")]
+    List {
+        /// 要查看的ZIP文件
+        archive: String,
+
+        /// 输出格式
+        #[arg(
+            short = 'f',
+            long = "format",
+            value_enum,
+            default_value_t = ListFormat::Table,
+            help_heading = "输出控制"
+        )]
+        format: ListFormat,
+    },
+    /// 检查压缩包是否损坏
+    ///
+    /// 读取每个条目并校验 CRC32 和大小，检查中央目录与本地文件头是否一致。
+    /// 支持 ZIP 和 tar 格式，发现损坏或被截断的条目时以非零状态退出。
+    ///
+    /// 示例:
+    ///
+    /// ```
+    /// ziper test dist.zip
+    ///
+    /// # 检查加密的压缩包
+    /// ziper test export.zip --password-file secret.txt
+    /// ```
+    Test {
+        /// 要检查的压缩文件
+        archive: String,
+    },
+}
+
+/// 列表输出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    Table,
+    Json,
+}
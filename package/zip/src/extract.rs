@@ -0,0 +1,319 @@
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use time::OffsetDateTime;
+use zip::extra_fields::ExtraField;
+use zip::read::ZipFile;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::password::PasswordSource;
+use crate::split::open_archive;
+
+/// 目标文件已存在时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// 报错并停止解压
+    Error,
+    Overwrite,
+    Skip,
+    Rename,
+}
+
+/// 解压结果统计
+#[derive(Debug, Default)]
+pub struct ExtractSummary {
+    pub files: usize,
+    pub directories: usize,
+    pub symlinks: usize,
+    pub skipped: usize,
+}
+
+pub fn extract_zip(
+    archive: &Path,
+    destination: &Path,
+    policy: ConflictPolicy,
+    passwords: &PasswordSource,
+) -> Result<ExtractSummary> {
+    let file = open_archive(archive)?;
+    let mut zip =
+        ZipArchive::new(file).with_context(|| format!("无效的ZIP文件: {}", archive.display()))?;
+
+    // 写入任何文件之前先检查所有路径，避免解压到一半才发现恶意条目
+    let mut entries = Vec::with_capacity(zip.len());
+    let mut encrypted = false;
+    for i in 0..zip.len() {
+        let entry = zip.by_index_raw(i)?;
+        let Some(path) = entry.enclosed_name() else {
+            bail!("拒绝解压不安全的路径: {}", entry.name());
+        };
+        encrypted |= entry.encrypted();
+        entries.push(path);
+    }
+
+    // 只有包含加密条目时才需要密码
+    let password = if encrypted {
+        Some(passwords.read(false)?)
+    } else {
+        None
+    };
+
+    fs::create_dir_all(destination)
+        .with_context(|| format!("无法创建目标目录: {}", destination.display()))?;
+    let root = destination
+        .canonicalize()
+        .with_context(|| format!("无法访问目标目录: {}", destination.display()))?;
+
+    let mut summary = ExtractSummary::default();
+    // 目录的权限和时间在所有文件写入后再设置，避免只读目录导致写入失败
+    let mut directories = Vec::new();
+
+    for (i, relative) in entries.into_iter().enumerate() {
+        let mut entry = match &password {
+            Some(password) if zip.by_index_raw(i)?.encrypted() => {
+                match zip.by_index_decrypt(i, password.as_bytes()) {
+                    Err(ZipError::InvalidPassword) => bail!("密码错误"),
+                    result => result?,
+                }
+            }
+            _ => zip.by_index(i)?,
+        };
+        let target = root.join(&relative);
+        ensure_parent_inside(&root, &target)?;
+
+        if entry.is_dir() {
+            if target.symlink_metadata().is_ok_and(|m| !m.is_dir()) {
+                bail!("无法创建目录，同名文件已存在: {}", relative.display());
+            }
+            fs::create_dir_all(&target)
+                .with_context(|| format!("无法创建目录: {}", target.display()))?;
+            directories.push((target, entry.unix_mode(), modified_time(&entry)));
+            summary.directories += 1;
+            continue;
+        }
+
+        let Some(target) = resolve_conflict(&target, policy)? else {
+            info!("Skipping existing: {}", relative.display());
+            summary.skipped += 1;
+            continue;
+        };
+
+        if entry.is_symlink() {
+            let mut link = String::new();
+            entry
+                .read_to_string(&mut link)
+                .with_context(|| format!("无法读取符号链接: {}", entry.name()))?;
+            check_symlink_target(&root, &target, Path::new(&link))?;
+
+            info!("Extracting: {} -> {}", relative.display(), link);
+            create_symlink(Path::new(&link), &target)?;
+            summary.symlinks += 1;
+            continue;
+        }
+
+        info!("Extracting: {}", relative.display());
+        let mut out =
+            File::create(&target).with_context(|| format!("无法创建文件: {}", target.display()))?;
+        io::copy(&mut entry, &mut out).with_context(|| format!("解压失败: {}", entry.name()))?;
+
+        set_permissions(&target, entry.unix_mode())?;
+        if let Some(mtime) = modified_time(&entry) {
+            if let Err(e) = out.set_modified(mtime) {
+                warn!(
+                    "Failed to set modification time {}: {}",
+                    target.display(),
+                    e
+                );
+            }
+        }
+        summary.files += 1;
+    }
+
+    // 由深到浅设置目录属性
+    directories.sort_by_key(|(path, _, _)| std::cmp::Reverse(path.components().count()));
+    for (path, mode, mtime) in directories {
+        if let Some(mtime) = mtime {
+            if let Err(e) = File::open(&path).and_then(|dir| dir.set_modified(mtime)) {
+                warn!("Failed to set modification time {}: {}", path.display(), e);
+            }
+        }
+        set_permissions(&path, mode)?;
+    }
+
+    Ok(summary)
+}
+
+/// 确认目标路径的父目录（可能经过已存在的符号链接）位于目标目录内，再逐级创建缺少的目录
+///
+/// 先检查已存在的最深一级目录，避免经过指向外部的符号链接在目标目录之外创建目录。
+fn ensure_parent_inside(root: &Path, target: &Path) -> Result<()> {
+    let outside = || {
+        anyhow!(
+            "拒绝解压到目标目录之外的路径: {}",
+            target.strip_prefix(root).unwrap_or(target).display()
+        )
+    };
+
+    let parent = target.parent().unwrap_or(root);
+    let mut missing = Vec::new();
+    let mut existing = parent;
+    while existing.symlink_metadata().is_err() {
+        missing.push(existing);
+        existing = existing.parent().ok_or_else(outside)?;
+    }
+
+    let real_existing = existing
+        .canonicalize()
+        .with_context(|| format!("无法访问目录: {}", existing.display()))?;
+    if !real_existing.starts_with(root) {
+        return Err(outside());
+    }
+
+    for dir in missing.into_iter().rev() {
+        if let Err(e) = fs::create_dir(dir) {
+            // 检查之后才出现的同名路径只接受真实的目录，不跟随符号链接
+            let is_dir = dir.symlink_metadata().is_ok_and(|m| m.is_dir());
+            if e.kind() != io::ErrorKind::AlreadyExists || !is_dir {
+                return Err(e).with_context(|| format!("无法创建目录: {}", dir.display()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 符号链接只能指向目标目录内部的相对路径
+fn check_symlink_target(root: &Path, link: &Path, target: &Path) -> Result<()> {
+    let escapes = target.has_root()
+        || target
+            .components()
+            .any(|c| matches!(c, Component::Prefix(_)))
+        || {
+            let base = link
+                .parent()
+                .and_then(|parent| parent.canonicalize().ok())
+                .unwrap_or_else(|| root.to_path_buf());
+            !resolve_existing(&base.join(target)).is_some_and(|path| path.starts_with(root))
+        };
+
+    if escapes {
+        bail!(
+            "拒绝解压指向目标目录之外的符号链接: {} -> {}",
+            link.strip_prefix(root).unwrap_or(link).display(),
+            target.display()
+        );
+    }
+    Ok(())
+}
+
+/// 解析路径实际指向的位置
+///
+/// 已存在的最深一级前缀通过 canonicalize 解析，从而跟随已经解压出的符号链接，
+/// 其余部分直接拼接。不存在的部分之后还有 `..` 时，最终位置取决于之后解压的条目，
+/// 无法确定，返回 None。
+fn resolve_existing(path: &Path) -> Option<PathBuf> {
+    let components: Vec<_> = path.components().collect();
+    (1..=components.len()).rev().find_map(|split| {
+        let real = components[..split]
+            .iter()
+            .collect::<PathBuf>()
+            .canonicalize()
+            .ok()?;
+        let rest = &components[split..];
+        if rest.contains(&Component::ParentDir) {
+            return None;
+        }
+        Some(rest.iter().fold(real, |path, c| path.join(c)))
+    })
+}
+
+/// 按冲突策略处理已存在的目标路径，返回实际写入的路径，跳过时返回 None
+fn resolve_conflict(target: &Path, policy: ConflictPolicy) -> Result<Option<PathBuf>> {
+    let Ok(metadata) = target.symlink_metadata() else {
+        return Ok(Some(target.to_path_buf()));
+    };
+
+    match policy {
+        ConflictPolicy::Error => bail!(
+            "目标文件已存在: {}（使用 --overwrite、--skip 或 --rename）",
+            target.display()
+        ),
+        ConflictPolicy::Skip => Ok(None),
+        ConflictPolicy::Overwrite => {
+            if metadata.is_dir() {
+                bail!("无法覆盖目录: {}", target.display());
+            }
+            fs::remove_file(target)
+                .with_context(|| format!("无法删除已存在的文件: {}", target.display()))?;
+            Ok(Some(target.to_path_buf()))
+        }
+        ConflictPolicy::Rename => {
+            let renamed = renamed_path(target);
+            info!("Renaming: {} -> {}", target.display(), renamed.display());
+            Ok(Some(renamed))
+        }
+    }
+}
+
+/// 生成不冲突的文件名，例如 `a.txt` -> `a (1).txt`
+fn renamed_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| candidate.symlink_metadata().is_err())
+        .expect("unbounded range always yields a free name")
+}
+
+/// 修改时间，优先使用扩展时间戳（UTC 秒），否则使用 DOS 时间
+pub fn modified_time(entry: &ZipFile) -> Option<SystemTime> {
+    let extended = entry.extra_data_fields().find_map(|field| match field {
+        ExtraField::ExtendedTimestamp(ts) => ts.mod_time(),
+        _ => None,
+    });
+    if let Some(seconds) = extended {
+        return Some(UNIX_EPOCH + Duration::from_secs(seconds.into()));
+    }
+
+    let datetime = OffsetDateTime::try_from(entry.last_modified()?).ok()?;
+    Some(datetime.into())
+}
+
+/// 恢复Unix权限，忽略 setuid/setgid 等特殊位
+#[cfg(unix)]
+fn set_permissions(path: &Path, mode: Option<u32>) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(mode) = mode {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o777))
+            .with_context(|| format!("无法设置权限: {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_permissions(_path: &Path, _mode: Option<u32>) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(original: &Path, link: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(original, link)
+        .with_context(|| format!("无法创建符号链接: {}", link.display()))
+}
+
+/// 不支持符号链接的平台上写入链接目标作为文件内容
+#[cfg(not(unix))]
+fn create_symlink(original: &Path, link: &Path) -> Result<()> {
+    fs::write(link, original.to_string_lossy().as_bytes())
+        .with_context(|| format!("无法创建文件: {}", link.display()))
+}
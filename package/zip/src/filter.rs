@@ -0,0 +1,56 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::warn;
+use std::path::Path;
+
+/// `--ignore` 和 `--include` 模式，按 gitignore 规则匹配相对于源目录的路径
+///
+/// 不含 `/` 的模式匹配任意层级的文件名，例如 `node_modules`；
+/// 含 `/` 或以 `/` 开头的模式相对源目录锚定，例如 `src/*.js`；
+/// `**` 匹配任意层级的目录，例如 `**/test/*.snap`。
+#[derive(Clone)]
+pub struct PathFilter {
+    ignore: Gitignore,
+    include: Option<Gitignore>,
+}
+
+impl PathFilter {
+    pub fn new(root: &Path, ignore_patterns: &[String], include_patterns: &[String]) -> Self {
+        let include = if include_patterns.is_empty() {
+            None
+        } else {
+            Some(build_matcher(root, include_patterns, "包含"))
+        };
+
+        Self {
+            ignore: build_matcher(root, ignore_patterns, "忽略"),
+            include,
+        }
+    }
+
+    /// 路径是否匹配 `--ignore`，被忽略的目录不再遍历
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.ignore.matched(path, is_dir).is_ignore()
+    }
+
+    /// 路径本身或其所在目录是否匹配 `--include`，未指定 `--include` 时包含所有路径
+    pub fn is_included(&self, path: &Path, is_dir: bool) -> bool {
+        self.include.as_ref().is_none_or(|include| {
+            include
+                .matched_path_or_any_parents(path, is_dir)
+                .is_ignore()
+        })
+    }
+}
+
+fn build_matcher(root: &Path, patterns: &[String], kind: &str) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in patterns {
+        if let Err(e) = builder.add_line(None, pattern) {
+            warn!("无效的{}模式 '{}': {}", kind, pattern, e);
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        warn!("无效的{}模式: {}", kind, e);
+        Gitignore::empty()
+    })
+}
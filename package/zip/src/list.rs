@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use time::OffsetDateTime;
+use zip::{AesMode, ZipArchive};
+
+use crate::extract::modified_time;
+use crate::split::open_archive;
+
+/// 压缩包中的一个条目
+#[derive(Debug, Serialize)]
+pub struct EntryInfo {
+    pub name: String,
+    /// 条目类型：file、dir 或 symlink
+    pub kind: &'static str,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub method: String,
+    pub crc32: u32,
+    pub unix_mode: Option<u32>,
+    /// 修改时间（Unix 时间戳，秒）
+    pub modified: Option<i64>,
+    pub encrypted: bool,
+    /// 加密方式：aes128、aes192、aes256 或 zipcrypto
+    pub encryption: Option<&'static str>,
+}
+
+impl EntryInfo {
+    /// 压缩节省的空间比例（百分比）
+    pub fn ratio(&self) -> f64 {
+        compression_ratio(self.size, self.compressed_size)
+    }
+
+    /// 类似 `ls -l` 的权限字符串，例如 `-rw-r--r--`
+    pub fn display_mode(&self) -> String {
+        let Some(mode) = self.unix_mode else {
+            return "-".to_string();
+        };
+        let kind = match self.kind {
+            "dir" => 'd',
+            "symlink" => 'l',
+            _ => '-',
+        };
+
+        let mut text = String::from(kind);
+        for shift in [6, 3, 0] {
+            let bits = (mode >> shift) & 0o7;
+            text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+            text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+            text.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+        }
+        text
+    }
+
+    pub fn display_modified(&self) -> String {
+        self.modified
+            .and_then(|seconds| OffsetDateTime::from_unix_timestamp(seconds).ok())
+            .map(|t| {
+                format!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                    t.year(),
+                    t.month() as u8,
+                    t.day(),
+                    t.hour(),
+                    t.minute(),
+                    t.second()
+                )
+            })
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+/// 所有条目的统计
+#[derive(Debug, Serialize)]
+pub struct ListSummary {
+    pub entries: usize,
+    pub files: usize,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub ratio: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Listing {
+    pub entries: Vec<EntryInfo>,
+    pub summary: ListSummary,
+}
+
+fn compression_ratio(size: u64, compressed_size: u64) -> f64 {
+    if size == 0 {
+        0.0
+    } else {
+        (1.0 - compressed_size as f64 / size as f64) * 100.0
+    }
+}
+
+/// 读取压缩包中所有条目的信息，不解压数据
+pub fn list_zip(archive: &Path) -> Result<Listing> {
+    let file = open_archive(archive)?;
+    let mut zip =
+        ZipArchive::new(file).with_context(|| format!("无效的ZIP文件: {}", archive.display()))?;
+
+    let mut entries = Vec::with_capacity(zip.len());
+    for i in 0..zip.len() {
+        // 条目信息位于未加密的中央目录，列出时不需要密码
+        let encryption = if zip.by_index_raw(i)?.encrypted() {
+            Some(encryption_name(&mut zip, i)?)
+        } else {
+            None
+        };
+
+        let entry = zip.by_index_raw(i)?;
+        let kind = if entry.is_dir() {
+            "dir"
+        } else if entry.is_symlink() {
+            "symlink"
+        } else {
+            "file"
+        };
+
+        entries.push(EntryInfo {
+            name: entry.name().to_string(),
+            kind,
+            size: entry.size(),
+            compressed_size: entry.compressed_size(),
+            method: entry.compression().to_string(),
+            crc32: entry.crc32(),
+            unix_mode: entry.unix_mode(),
+            modified: modified_time(&entry).map(unix_seconds),
+            encrypted: entry.encrypted(),
+            encryption,
+        });
+    }
+
+    let size = entries.iter().map(|e| e.size).sum();
+    let compressed_size = entries.iter().map(|e| e.compressed_size).sum();
+    let summary = ListSummary {
+        entries: entries.len(),
+        files: entries.iter().filter(|e| e.kind == "file").count(),
+        size,
+        compressed_size,
+        ratio: compression_ratio(size, compressed_size),
+    };
+
+    Ok(Listing { entries, summary })
+}
+
+/// 加密条目的加密方式，没有 AES 额外字段时为传统的 ZipCrypto
+fn encryption_name(zip: &mut ZipArchive<File>, index: usize) -> Result<&'static str> {
+    let name = match zip.get_aes_verification_key_and_salt(index)? {
+        Some(info) => match info.aes_mode {
+            AesMode::Aes128 => "aes128",
+            AesMode::Aes192 => "aes192",
+            AesMode::Aes256 => "aes256",
+        },
+        None => "zipcrypto",
+    };
+    Ok(name)
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    }
+}
+
+pub fn print_table(listing: &Listing) {
+    println!(
+        "{}  {}  {}  {}  {}  {}  {}  名称",
+        pad("大小", 12, true),
+        pad("压缩后", 12, true),
+        pad("压缩率", 6, true),
+        pad("方法", 8, false),
+        pad("CRC32", 8, false),
+        pad("权限", 10, false),
+        pad("修改时间", 19, false),
+    );
+    println!("{}", "-".repeat(100));
+
+    for entry in &listing.entries {
+        let mut name = entry.name.clone();
+        if entry.encrypted {
+            name.push_str(" *");
+        }
+        println!(
+            "{:>12}  {:>12}  {:>5.1}%  {:<8}  {:08x}  {:<10}  {:<19}  {}",
+            entry.size,
+            entry.compressed_size,
+            entry.ratio(),
+            entry.method,
+            entry.crc32,
+            entry.display_mode(),
+            entry.display_modified(),
+            name
+        );
+    }
+
+    let summary = &listing.summary;
+    println!("{}", "-".repeat(100));
+    println!(
+        "{:>12}  {:>12}  {:>5.1}%  共 {} 个条目，{} 个文件",
+        summary.size, summary.compressed_size, summary.ratio, summary.entries, summary.files
+    );
+}
+
+/// 按显示宽度填充，中文字符占两列
+fn pad(text: &str, width: usize, right_align: bool) -> String {
+    let text_width: usize = text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum();
+    let padding = " ".repeat(width.saturating_sub(text_width));
+    if right_align {
+        format!("{}{}", padding, text)
+    } else {
+        format!("{}{}", text, padding)
+    }
+}
+
+pub fn print_json(listing: &Listing) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(listing)?);
+    Ok(())
+}
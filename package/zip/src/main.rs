@@ -1,89 +1,27 @@
-use anyhow::{Context, Result};
-use clap::Parser;
-use glob::Pattern;
-use log::{error, info, warn, LevelFilter};
+use anyhow::{bail, Context, Result};
+use archive::{ArchiveFormat, WriteOptions};
+use cli::{Cli, Commands, ListFormat};
+use extract::ConflictPolicy;
+use filter::PathFilter;
+use ignore::WalkBuilder;
+use log::{debug, error, info, log_enabled, warn, Level, LevelFilter};
+use password::PasswordSource;
 use path_clean::clean;
-use std::fs::File;
-use std::io::{self, Write};
+use split::{SplitMode, SplitOptions};
+use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
-use zip::write::{ExtendedFileOptions, FileOptions};
-
-/// 快速的文件压缩工具
-///
-/// 一个简单易用的ZIP压缩工具，用于将文件或目录压缩为ZIP格式。
-/// 支持指定输出文件名和路径，忽略特定文件或目录，以及不同的输出详细级别。
-#[derive(Parser)]
-#[command(author, version, about, long_about = None)]
-#[command(after_help = "示例:
-
-```
-# 基本用法：压缩目录为 [目录名].zip
-ziper dist
-
-# 指定输出文件
-ziper dist output.zip
-
-# 指定输出目录和文件名
-ziper dist path/to/output.zip
-
-# 忽略特定模式
-ziper dist --ignore \"node_modules,.git,*.zip\"
-
-# 使用静默模式
-ziper dist -q
-
-# 使用详细模式
-ziper dist -v
-```")]
-struct Cli {
-    /// 要压缩的源文件或目录
-    ///
-    /// 指定需要被压缩的文件或目录的路径。
-    /// 如果是目录，将递归压缩其中的所有内容。
-    source: Option<String>,
-
-    /// 输出的zip文件路径
-    ///
-    /// 指定生成的ZIP文件的路径和名称。
-    /// 如果不提供，默认使用源名称加.zip后缀。
-    output: Option<String>,
-
-    /// 要忽略的模式，使用逗号分隔
-    ///
-    /// 指定在压缩过程中要忽略的文件或目录模式。
-    /// 支持glob模式，多个模式用逗号分隔。
-    /// 例如："node_modules,.git,*.zip"
-    #[arg(
-        short = 'i',
-        long = "ignore",
-        value_delimiter = ',',
-        help_heading = "过滤选项"
-    )]
-    ignore_patterns: Option<Vec<String>>,
-
-    /// 静默模式 - 不显示输出
-    ///
-    /// 在压缩过程中不显示任何进度信息，除非发生错误。
-    #[arg(
-        short = 'q',
-        long = "quiet",
-        conflicts_with = "verbose",
-        help_heading = "输出控制"
-    )]
-    quiet: bool,
-
-    /// 详细模式 - 显示详细输出
-    ///
-    /// 在压缩过程中显示详细的进度信息，包括每个被处理的文件。
-    #[arg(
-        short = 'v',
-        long = "verbose",
-        conflicts_with = "quiet",
-        help_heading = "输出控制"
-    )]
-    verbose: bool,
-}
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+mod archive;
+mod cli;
+mod extract;
+mod filter;
+mod list;
+mod parallel;
+mod password;
+mod split;
+mod verify;
 
 fn setup_logger(quiet: bool, verbose: bool) {
     let level = if quiet {
@@ -106,53 +44,114 @@ fn setup_logger(quiet: bool, verbose: bool) {
         .init();
 }
 
-fn should_ignore(path: &Path, ignore_patterns: &[Pattern]) -> bool {
-    let path_str = path.to_string_lossy();
+/// 文件标识，用于识别源目录中的输出文件，不存在时返回 None
+#[cfg(unix)]
+fn file_id(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
 
-    // 首先检查完整路径
-    for pattern in ignore_patterns {
-        if pattern.matches(&path_str) {
-            return true;
-        }
-    }
+    fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
 
-    // 检查每个路径组件
-    for component in path.components() {
-        let comp_str = component.as_os_str().to_string_lossy();
-        for pattern in ignore_patterns {
-            if pattern.matches(&comp_str) {
-                return true;
-            }
-        }
+#[cfg(not(unix))]
+fn file_id(path: &Path) -> Option<PathBuf> {
+    path.canonicalize().ok()
+}
+
+/// 可重现模式下修改时间的上限，取自 SOURCE_DATE_EPOCH，未设置时为 ZIP 能表示的最早时间 1980-01-01
+fn source_date_epoch() -> Result<SystemTime> {
+    let seconds = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .with_context(|| format!("无效的 SOURCE_DATE_EPOCH: {}", value))?,
+        Err(_) => 315_532_800,
+    };
+    Ok(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// 遍历时发现的符号链接循环
+fn is_loop(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+        _ => false,
     }
+}
 
-    false
+/// 遍历得到的条目类型
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    File,
+    Directory,
+    Symlink,
 }
 
-fn create_zip(source: &Path, output: &Path, ignore_patterns: &[Pattern]) -> Result<()> {
-    let file = File::create(output).context("Failed to create zip file")?;
-    let mut zip = zip::ZipWriter::new(file);
+/// 待写入压缩包的条目
+struct Entry {
+    kind: EntryKind,
+    name: String,
+    path: PathBuf,
+}
+
+fn create_archive(
+    source: &Path,
+    output: &Path,
+    format: ArchiveFormat,
+    options: &WriteOptions,
+    filter: &PathFilter,
+    use_vcs_ignore: bool,
+    jobs: usize,
+) -> Result<()> {
+    // 先写入同一目录下的临时文件，成功后再原子地重命名，失败时临时文件被自动删除
+    let temp = archive::temp_file_for(output).context("Failed to create archive file")?;
+    let file = temp.reopen().context("Failed to create archive file")?;
+    let mut writer = archive::new_writer(format, file, options)?;
+
+    // 输出文件位于源目录中时跳过它、它的分卷和临时文件
+    let volumes = split::existing_volumes(output);
+    let output_ids: Vec<_> = [temp.path(), output]
+        .into_iter()
+        .chain(volumes.iter().map(PathBuf::as_path))
+        .filter_map(file_id)
+        .collect();
 
     let source_path = clean(source);
     let source_name = source_path.file_name().unwrap_or_default();
 
-    // 使用 WalkDir 的配置选项来更好地处理错误
-    let walker = WalkDir::new(&source_path)
-        .follow_links(false) // 不跟随符号链接
+    // 使用 WalkBuilder 的配置选项来更好地处理错误
+    let mut walker = WalkBuilder::new(&source_path);
+    walker
+        .follow_links(options.follow_symlinks) // 默认保存符号链接本身
         .same_file_system(true) // 保持在同一个文件系统内
-        .contents_first(false); // 目录优先
+        .hidden(false) // 包含隐藏文件
+        .parents(false) // 只读取源目录内的忽略文件
+        .require_git(false) // 不在 git 仓库中也读取 .gitignore
+        .git_global(false)
+        .git_ignore(use_vcs_ignore)
+        .git_exclude(use_vcs_ignore)
+        .ignore(use_vcs_ignore)
+        .add_custom_ignore_filename(".zipignore")
+        .sort_by_file_name(|a, b| a.cmp(b)); // 固定条目顺序，输出与文件系统无关
+
+    // 在遍历时剪除被忽略的目录，不再访问其中的文件
+    let prune = filter.clone();
+    walker.filter_entry(move |entry| {
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        if entry.depth() > 0 && prune.is_ignored(entry.path(), is_dir) {
+            info!("Ignoring: {}", entry.path().display());
+            return false;
+        }
+        true
+    });
 
-    for entry in walker {
+    let mut entries = Vec::new();
+    for entry in walker.build() {
         match entry {
             Ok(entry) => {
                 let path = entry.path();
 
-                // Skip if path matches any ignore pattern
-                if should_ignore(path, ignore_patterns) {
-                    info!("Ignoring: {}", path.display());
-                    continue;
-                }
-
                 let relative_path = if path == source_path {
                     PathBuf::from(source_name)
                 } else {
@@ -160,49 +159,187 @@ fn create_zip(source: &Path, output: &Path, ignore_patterns: &[Pattern]) -> Resu
                     PathBuf::from(source_name).join(stripped_path)
                 };
 
-                if path.is_file() {
-                    match File::open(path) {
-                        Ok(mut f) => {
-                            info!("Adding: {}", relative_path.display());
-                            let options = FileOptions::<ExtendedFileOptions>::default()
-                                .compression_method(zip::CompressionMethod::Deflated)
-                                .unix_permissions(0o755);
-                            if let Err(e) = zip
-                                .start_file(relative_path.to_string_lossy().into_owned(), options)
-                            {
-                                warn!("Failed to add file {}: {}", path.display(), e);
-                                continue;
-                            }
-                            if let Err(e) = io::copy(&mut f, &mut zip) {
-                                warn!("Failed to copy file {}: {}", path.display(), e);
-                                continue;
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to open file {}: {}", path.display(), e);
-                            continue;
-                        }
+                let Some(file_type) = entry.file_type() else {
+                    continue;
+                };
+
+                if file_type.is_file() && file_id(path).is_some_and(|id| output_ids.contains(&id)) {
+                    info!("Skipping output archive: {}", path.display());
+                    continue;
+                }
+
+                // 指定 --include 时只写入匹配的文件，目录只在本身匹配时写入
+                if entry.depth() > 0 && !filter.is_included(path, file_type.is_dir()) {
+                    if !file_type.is_dir() {
+                        debug!("Not included: {}", path.display());
                     }
-                } else if !path.is_dir() {
-                    warn!("Skipping non-regular file: {}", path.display());
+                    continue;
                 }
+                let kind = if file_type.is_file() {
+                    EntryKind::File
+                } else if file_type.is_dir() {
+                    EntryKind::Directory
+                } else if file_type.is_symlink() {
+                    EntryKind::Symlink
+                } else {
+                    warn!("Skipping non-regular file: {}", path.display());
+                    continue;
+                };
+
+                entries.push(Entry {
+                    kind,
+                    name: relative_path.to_string_lossy().into_owned(),
+                    path: path.to_path_buf(),
+                });
             }
             Err(e) => {
                 // 只是警告而不是中断整个过程
-                warn!("Failed to access path: {}", e);
+                if is_loop(&e) {
+                    warn!("Skipping symlink loop: {}", e);
+                } else {
+                    warn!("Failed to access path: {}", e);
+                }
                 continue;
             }
         }
     }
 
-    zip.finish()?;
+    // 文件在多个线程中压缩，再按遍历顺序写入，输出与线程数无关
+    let encoder = writer.encoder();
+    parallel::for_each_ordered(
+        &entries,
+        jobs,
+        |entry| (entry.kind == EntryKind::File).then(|| encoder.encode(&entry.name, &entry.path)),
+        |entry, encoded| {
+            let result = if let Some(encoded) = encoded {
+                info!("Adding: {}", entry.name);
+                encoded.and_then(|file| writer.add_file(file))
+            } else if entry.kind == EntryKind::Directory {
+                writer.add_directory(&entry.name, &entry.path)
+            } else {
+                info!("Adding: {}", entry.name);
+                writer.add_symlink(&entry.name, &entry.path)
+            };
+
+            if let Err(e) = result {
+                warn!("Failed to add file {}: {:#}", entry.path.display(), e);
+            }
+            Ok(())
+        },
+    )?;
+
+    writer.finish()?;
+    let written = match options.split {
+        Some(split) => {
+            let volumes = split::write_volumes(temp, output, split)?;
+            if volumes.len() > 1 {
+                info!("已拆分为 {} 个分卷", volumes.len());
+            }
+            volumes
+        }
+        None => {
+            temp.persist(output)
+                .with_context(|| format!("无法写入压缩文件: {}", output.display()))?;
+            vec![output.to_path_buf()]
+        }
+    };
+
+    // 检查最终写入的文件，校验失败时删除，不留下损坏的压缩包
+    if options.verify {
+        if let Err(e) = verify_output(output, format, &options.password) {
+            for path in &written {
+                if let Err(e) = fs::remove_file(path) {
+                    warn!("无法删除 {}: {}", path.display(), e);
+                }
+            }
+            return Err(e);
+        }
+    }
+
+    // ZIP 的压缩后大小在写入完成后才能确定，详细模式下读回显示每个文件的压缩率
+    if format == ArchiveFormat::Zip && log_enabled!(Level::Debug) {
+        for entry in list::list_zip(output)?.entries {
+            if entry.kind == "file" {
+                debug!(
+                    "{}: {} -> {} ({:.1}%, {})",
+                    entry.name,
+                    entry.size,
+                    entry.compressed_size,
+                    entry.ratio(),
+                    entry.method
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 检查写入完成的压缩包，分卷压缩包会先合并所有分卷
+fn verify_output(output: &Path, format: ArchiveFormat, password: &str) -> Result<()> {
+    let report =
+        verify::test_path(output, Some(format), || Ok(password.to_string())).context("校验失败")?;
+    if !report.damaged.is_empty() {
+        bail!("校验失败: {} 个条目已损坏", report.damaged.len());
+    }
+    info!("校验通过: {} 个条目", report.entries);
     Ok(())
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
     setup_logger(cli.quiet, cli.verbose);
 
+    if let Some(Commands::List { archive, format }) = cli.command {
+        let listing = list::list_zip(Path::new(&archive))?;
+        match format {
+            ListFormat::Table => list::print_table(&listing),
+            ListFormat::Json => list::print_json(&listing)?,
+        }
+        return Ok(());
+    }
+
+    if let Some(Commands::Test { archive }) = cli.command {
+        let passwords = PasswordSource::new(cli.password_file);
+        let report = verify::test_path(Path::new(&archive), None, || passwords.read(false))?;
+        if !report.damaged.is_empty() {
+            bail!(
+                "发现 {} 个损坏的条目，共 {} 个条目",
+                report.damaged.len(),
+                report.entries
+            );
+        }
+        info!("没有发现错误: {} 个条目", report.entries);
+        return Ok(());
+    }
+
+    if let Some(Commands::Extract {
+        archive,
+        destination,
+        overwrite,
+        skip,
+        rename,
+    }) = cli.command
+    {
+        let policy = if overwrite {
+            ConflictPolicy::Overwrite
+        } else if skip {
+            ConflictPolicy::Skip
+        } else if rename {
+            ConflictPolicy::Rename
+        } else {
+            ConflictPolicy::Error
+        };
+        let destination = PathBuf::from(destination.unwrap_or_else(|| ".".to_string()));
+
+        let passwords = PasswordSource::new(cli.password_file);
+        let summary = extract::extract_zip(Path::new(&archive), &destination, policy, &passwords)?;
+        info!(
+            "解压完成: {} 个文件, {} 个目录, {} 个符号链接, 跳过 {} 个",
+            summary.files, summary.directories, summary.symlinks, summary.skipped
+        );
+        return Ok(());
+    }
+
     // 如果没有提供源路径，显示帮助信息
     if cli.source.is_none() {
         eprintln!("错误: 必须提供源文件或目录路径");
@@ -219,32 +356,78 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    // 确定输出路径
-    let output = match cli.output {
-        Some(path) => PathBuf::from(path),
-        None => {
-            let mut path = source_path.to_path_buf();
-            path.set_extension("zip");
-            path
-        }
+    // 确定输出路径和格式：--format 优先，其次根据输出文件扩展名推断，默认为 zip
+    let output = cli.output.map(PathBuf::from);
+    let format = cli
+        .format
+        .or_else(|| output.as_deref().and_then(ArchiveFormat::from_path))
+        .unwrap_or(ArchiveFormat::Zip);
+    let output = output.unwrap_or_else(|| {
+        let mut path = source_path.to_path_buf();
+        path.set_extension(format.extension());
+        path
+    });
+
+    // 忽略和包含模式相对于源目录匹配
+    let source_root = if source_path.is_dir() {
+        clean(source_path)
+    } else {
+        clean(source_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
     };
+    let filter = PathFilter::new(
+        &source_root,
+        &cli.ignore_patterns.unwrap_or_default(),
+        &cli.include_patterns.unwrap_or_default(),
+    );
+
+    let reproducible = if cli.reproducible {
+        Some(source_date_epoch()?)
+    } else {
+        None
+    };
+    let mut options = WriteOptions {
+        method: cli.method,
+        level: cli.level,
+        normalize_permissions: cli.normalize_permissions || reproducible.is_some(),
+        follow_symlinks: cli.follow_symlinks,
+        reproducible,
+        encryption: cli.encrypt,
+        password: String::new(),
+        split: cli.split_size.map(|size| SplitOptions {
+            size,
+            // ZIP 默认使用标准分卷，其他格式只能按字节切分
+            mode: cli.split_mode.unwrap_or(if format == ArchiveFormat::Zip {
+                SplitMode::Spanned
+            } else {
+                SplitMode::Numbered
+            }),
+        }),
+        verify: cli.verify,
+    };
+    options.validate(format)?;
+    if options.encryption.is_some() {
+        options.password = PasswordSource::new(cli.password_file).read(true)?;
+    }
 
-    // 编译忽略模式
-    let ignore_patterns: Vec<Pattern> = cli
-        .ignore_patterns
-        .unwrap_or_default()
-        .iter()
-        .filter_map(|pattern| match Pattern::new(pattern) {
-            Ok(p) => Some(p),
-            Err(e) => {
-                warn!("无效的忽略模式 '{}': {}", pattern, e);
-                None
-            }
-        })
-        .collect();
-
-    // 创建 zip 文件
-    create_zip(source_path, &output, &ignore_patterns)?;
+    // 默认使用所有可用的 CPU 核心
+    let jobs = cli
+        .jobs
+        .or_else(|| std::thread::available_parallelism().ok())
+        .map_or(1, |n| n.get());
+
+    // 创建压缩包
+    create_archive(
+        source_path,
+        &output,
+        format,
+        &options,
+        &filter,
+        !cli.no_vcs_ignore,
+        jobs,
+    )?;
 
     Ok(())
 }
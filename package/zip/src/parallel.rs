@@ -0,0 +1,82 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::sync::{mpsc, Condvar, Mutex};
+use std::thread;
+
+/// 调度状态
+struct State {
+    /// 下一个待处理的索引
+    next: usize,
+    /// 已按顺序消费的数量
+    consumed: usize,
+    /// 消费出错后停止调度
+    stopped: bool,
+}
+
+/// 使用 `jobs` 个线程并行执行 `map`，并按输入顺序把结果交给 `consume`
+///
+/// 结果的顺序与线程数无关。已完成但尚未消费的结果最多为 `jobs * 4` 个，
+/// 避免某个大文件耗时较长时其余结果堆积在内存中。`consume` 出错时停止调度并返回该错误。
+pub fn for_each_ordered<T, R, F, C>(items: &[T], jobs: usize, map: F, mut consume: C) -> Result<()>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    C: FnMut(&T, R) -> Result<()>,
+{
+    let jobs = jobs.max(1);
+    let window = jobs * 4;
+    let state = Mutex::new(State {
+        next: 0,
+        consumed: 0,
+        stopped: false,
+    });
+    let ready = Condvar::new();
+
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..jobs.min(items.len()) {
+            let tx = tx.clone();
+            let (state, ready, map) = (&state, &ready, &map);
+            scope.spawn(move || loop {
+                let index = {
+                    let mut state = state.lock().unwrap();
+                    while !state.stopped
+                        && state.next < items.len()
+                        && state.next >= state.consumed + window
+                    {
+                        state = ready.wait(state).unwrap();
+                    }
+                    if state.stopped || state.next >= items.len() {
+                        break;
+                    }
+                    state.next += 1;
+                    state.next - 1
+                };
+                if tx.send((index, map(&items[index]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        let result = rx.iter().try_for_each(|(index, value)| {
+            pending.insert(index, value);
+            while let Some(value) = pending.remove(&next) {
+                consume(&items[next], value)?;
+                next += 1;
+                state.lock().unwrap().consumed = next;
+                ready.notify_all();
+            }
+            Ok(())
+        });
+
+        if result.is_err() {
+            state.lock().unwrap().stopped = true;
+            ready.notify_all();
+        }
+        result
+    })
+}
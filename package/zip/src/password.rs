@@ -0,0 +1,86 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+/// 存放密码的环境变量
+pub const PASSWORD_ENV: &str = "ZIPER_PASSWORD";
+
+/// 密码来源，依次为 `--password-file`、`ZIPER_PASSWORD` 环境变量和终端输入
+///
+/// 密码不从命令行参数读取，避免出现在 shell 历史和进程列表中。
+pub struct PasswordSource {
+    file: Option<PathBuf>,
+}
+
+impl PasswordSource {
+    pub fn new(file: Option<PathBuf>) -> Self {
+        Self { file }
+    }
+
+    /// 读取密码，`confirm` 为 true 时在终端中要求输入两次
+    pub fn read(&self, confirm: bool) -> Result<String> {
+        let password = if let Some(file) = &self.file {
+            let content = fs::read_to_string(file)
+                .with_context(|| format!("无法读取密码文件: {}", file.display()))?;
+            // 只去掉结尾的换行，保留密码中的空格
+            content.lines().next().unwrap_or_default().to_string()
+        } else if let Ok(password) = std::env::var(PASSWORD_ENV) {
+            password
+        } else {
+            prompt(confirm)?
+        };
+
+        if password.is_empty() {
+            bail!("密码不能为空");
+        }
+        Ok(password)
+    }
+}
+
+fn prompt(confirm: bool) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        bail!(
+            "需要密码：请使用 --password-file 或 {} 环境变量提供密码",
+            PASSWORD_ENV
+        );
+    }
+
+    let password = read_hidden("密码: ")?;
+    if confirm && read_hidden("确认密码: ")? != password {
+        bail!("两次输入的密码不一致");
+    }
+    Ok(password)
+}
+
+/// 在终端中读取一行，输入时不回显
+fn read_hidden(message: &str) -> Result<String> {
+    eprint!("{}", message);
+    io::stderr().flush()?;
+
+    let echo_disabled = set_echo(false);
+    let mut line = String::new();
+    let result = io::stdin().lock().read_line(&mut line);
+    if echo_disabled {
+        set_echo(true);
+        eprintln!();
+    }
+    result.context("无法读取密码")?;
+
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// 通过 stty 切换终端回显，失败时返回 false，此时输入仍会显示
+#[cfg(unix)]
+fn set_echo(enabled: bool) -> bool {
+    std::process::Command::new("stty")
+        .arg(if enabled { "echo" } else { "-echo" })
+        .stdin(std::process::Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn set_echo(_enabled: bool) -> bool {
+    false
+}
@@ -0,0 +1,765 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+use crate::archive::temp_file_for;
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR: u32 = 0x0706_4b50;
+/// 分卷压缩包第一卷开头的标记
+const SPLIT_SIGNATURE: u32 = 0x0807_4b50;
+
+/// 分卷的最小大小，需要能容纳最长的文件头
+pub const MIN_SPLIT_SIZE: u64 = 64 * 1024;
+
+/// 分卷方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SplitMode {
+    /// ZIP 标准分卷：`.z01`、`.z02`……最后一卷为 `.zip`
+    Spanned,
+    /// 按字节切分为 `.001`、`.002`……，适用于任何格式
+    Numbered,
+}
+
+/// 分卷选项
+#[derive(Debug, Clone, Copy)]
+pub struct SplitOptions {
+    /// 每卷的最大字节数
+    pub size: u64,
+    pub mode: SplitMode,
+}
+
+/// 解析分卷大小，支持 K、M、G 后缀（1024 进制），例如 `500M`
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => text.split_at(index),
+        None => (text, ""),
+    };
+    let multiplier: u64 = match unit.to_ascii_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return Err(format!("无效的大小单位: {}（支持 K、M、G）", unit)),
+    };
+    let size = number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("无效的大小: {}", text))?;
+
+    if size < MIN_SPLIT_SIZE {
+        return Err("分卷大小不能小于 64K".to_string());
+    }
+    Ok(size)
+}
+
+/// 第 `index` 卷（从 0 开始）的路径，ZIP 分卷为 `.z01`，编号分卷为 `.001`
+fn volume_path(output: &Path, mode: SplitMode, index: usize) -> PathBuf {
+    match mode {
+        SplitMode::Spanned => output.with_extension(format!("z{:02}", index + 1)),
+        SplitMode::Numbered => {
+            let mut name = output.as_os_str().to_owned();
+            name.push(format!(".{:03}", index + 1));
+            PathBuf::from(name)
+        }
+    }
+}
+
+/// 从第 `from` 卷开始连续存在的分卷
+fn volumes_from(output: &Path, mode: SplitMode, from: usize) -> Vec<PathBuf> {
+    (from..)
+        .map(|index| volume_path(output, mode, index))
+        .take_while(|path| path.exists())
+        .collect()
+}
+
+/// 已存在的 `output` 的所有分卷
+pub fn existing_volumes(output: &Path) -> Vec<PathBuf> {
+    let mut volumes = volumes_from(output, SplitMode::Spanned, 0);
+    volumes.extend(volumes_from(output, SplitMode::Numbered, 0));
+    volumes
+}
+
+/// 将写入完成的压缩包写出为分卷，返回写入的文件
+///
+/// ZIP 分卷中每个文件头、中央目录记录和结尾记录都完整地位于一卷中，并按所在分卷更新卷号和偏移；
+/// 压缩包不超过分卷大小时直接写入 `output`。编号分卷只是按字节切分。
+pub fn write_volumes(
+    mut archive: NamedTempFile,
+    output: &Path,
+    options: SplitOptions,
+) -> Result<Vec<PathBuf>> {
+    let len = archive.as_file().metadata()?.len();
+    if options.mode == SplitMode::Spanned && len <= options.size {
+        archive
+            .persist(output)
+            .with_context(|| format!("无法写入压缩文件: {}", output.display()))?;
+        remove_stale(output, options.mode, 0)?;
+        return Ok(vec![output.to_path_buf()]);
+    }
+
+    let mut volumes = VolumeWriter::new(output, options.size)?;
+    let file = archive.as_file_mut();
+    file.rewind()?;
+    match options.mode {
+        SplitMode::Spanned => {
+            volumes.write_all(&SPLIT_SIGNATURE.to_le_bytes())?;
+            rewrite(file, &|_, offset| Ok(offset), &mut volumes)?;
+        }
+        SplitMode::Numbered => {
+            io::copy(file, &mut volumes)?;
+        }
+    }
+    volumes.finish(output, options.mode)
+}
+
+/// 删除上次运行留下的多余分卷
+fn remove_stale(output: &Path, mode: SplitMode, from: usize) -> Result<()> {
+    for path in volumes_from(output, mode, from) {
+        fs::remove_file(&path).with_context(|| format!("无法删除旧的分卷: {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// 打开压缩包，分卷压缩包会先合并为一个临时文件
+///
+/// 可以指定 `.zip`、任意一个 `.z01` 分卷，或者编号分卷的 `.001` 及去掉编号的名称。
+pub fn open_archive(archive: &Path) -> Result<File> {
+    if let Some(parts) = numbered_parts(archive) {
+        let mut joined = tempfile::tempfile()?;
+        for part in parts {
+            let mut file =
+                File::open(&part).with_context(|| format!("无法打开分卷: {}", part.display()))?;
+            io::copy(&mut file, &mut joined)?;
+        }
+        joined.rewind()?;
+        return Ok(joined);
+    }
+
+    // 指定 .z01 等分卷时打开最后一卷 .zip
+    let is_volume = archive
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.len() >= 3 && ext.starts_with('z') && is_digits(&ext[1..]));
+    let last = if is_volume {
+        archive.with_extension("zip")
+    } else {
+        archive.to_path_buf()
+    };
+
+    let mut file =
+        File::open(&last).with_context(|| format!("无法打开压缩文件: {}", last.display()))?;
+    let disk = match find_end_record(&mut file) {
+        Ok((_, end)) => u16_at(&end, 4),
+        // 交给 zip 库报告具体的错误
+        Err(_) => 0,
+    };
+    if disk == 0 {
+        file.rewind()?;
+        return Ok(file);
+    }
+
+    let mut files = Vec::new();
+    for index in 0..usize::from(disk) {
+        let path = volume_path(&last, SplitMode::Spanned, index);
+        files.push(File::open(&path).with_context(|| format!("缺少分卷: {}", path.display()))?);
+    }
+    files.push(file);
+
+    let mut volumes = Volumes::new(files)?;
+    let starts = volumes.starts.clone();
+    let mut joined = Plain::new(tempfile::tempfile()?);
+    rewrite(
+        &mut volumes,
+        &|disk, offset| match starts.get(disk as usize) {
+            Some(start) => Ok(start + offset),
+            None => bail!("缺少第 {} 个分卷", disk + 1),
+        },
+        &mut joined,
+    )
+    .with_context(|| format!("无法合并分卷: {}", last.display()))?;
+
+    let mut joined = joined.into_inner();
+    joined.rewind()?;
+    Ok(joined)
+}
+
+/// 编号分卷的所有部分，不是编号分卷时返回 None
+fn numbered_parts(archive: &Path) -> Option<Vec<PathBuf>> {
+    let base = match archive.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.len() == 3 && is_digits(ext) => archive.with_extension(""),
+        _ if !archive.exists() => archive.to_path_buf(),
+        _ => return None,
+    };
+    let parts = volumes_from(&base, SplitMode::Numbered, 0);
+    (!parts.is_empty()).then_some(parts)
+}
+
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// 按大小切换到下一卷的写入器，分卷先写入临时文件
+struct VolumeWriter<'a> {
+    output: &'a Path,
+    size: u64,
+    volumes: Vec<NamedTempFile>,
+    /// 当前卷已写入的字节数
+    written: u64,
+}
+
+impl<'a> VolumeWriter<'a> {
+    fn new(output: &'a Path, size: u64) -> Result<Self> {
+        let mut writer = Self {
+            output,
+            size,
+            volumes: Vec::new(),
+            written: 0,
+        };
+        writer.next_volume()?;
+        Ok(writer)
+    }
+
+    fn next_volume(&mut self) -> io::Result<()> {
+        self.volumes.push(temp_file_for(self.output)?);
+        self.written = 0;
+        Ok(())
+    }
+
+    /// 把分卷重命名为最终的文件名，ZIP 分卷的最后一卷为 `output`
+    fn finish(self, output: &Path, mode: SplitMode) -> Result<Vec<PathBuf>> {
+        let count = self.volumes.len();
+        let mut paths = Vec::with_capacity(count);
+        for (index, volume) in self.volumes.into_iter().enumerate() {
+            let path = if mode == SplitMode::Spanned && index + 1 == count {
+                output.to_path_buf()
+            } else {
+                volume_path(output, mode, index)
+            };
+            volume
+                .persist(&path)
+                .with_context(|| format!("无法写入分卷: {}", path.display()))?;
+            paths.push(path);
+        }
+
+        let numbered = paths.len() - usize::from(mode == SplitMode::Spanned);
+        remove_stale(output, mode, numbered)?;
+        Ok(paths)
+    }
+}
+
+impl Write for VolumeWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written >= self.size {
+            self.next_volume()?;
+        }
+        let available = usize::try_from(self.size - self.written).unwrap_or(usize::MAX);
+        let volume = self.volumes.last_mut().expect("at least one volume");
+        let written = volume.write(&buf[..buf.len().min(available)])?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.volumes.last_mut() {
+            Some(volume) => volume.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Sink for VolumeWriter<'_> {
+    fn position(&self) -> (u32, u64) {
+        (self.volumes.len() as u32 - 1, self.written)
+    }
+
+    fn keep_together(&mut self, len: u64) -> io::Result<()> {
+        if self.written > 0 && self.written + len > self.size {
+            self.next_volume()?;
+        }
+        Ok(())
+    }
+}
+
+/// 多个分卷首尾相接组成的只读流
+struct Volumes {
+    files: Vec<File>,
+    /// 每卷在流中的起始偏移
+    starts: Vec<u64>,
+    len: u64,
+    position: u64,
+}
+
+impl Volumes {
+    fn new(files: Vec<File>) -> io::Result<Self> {
+        let mut starts = Vec::with_capacity(files.len());
+        let mut len = 0;
+        for file in &files {
+            starts.push(len);
+            len += file.metadata()?.len();
+        }
+        Ok(Self {
+            files,
+            starts,
+            len,
+            position: 0,
+        })
+    }
+}
+
+impl Read for Volumes {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.len {
+            return Ok(0);
+        }
+        let index = self.starts.partition_point(|&start| start <= self.position) - 1;
+        let end = self.starts.get(index + 1).copied().unwrap_or(self.len);
+        let available = usize::try_from(end - self.position).unwrap_or(usize::MAX);
+
+        let file = &mut self.files[index];
+        file.seek(SeekFrom::Start(self.position - self.starts[index]))?;
+        let len = buf.len().min(available);
+        let read = file.read(&mut buf[..len])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for Volumes {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position =
+            position.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek"))?;
+        Ok(self.position)
+    }
+}
+
+/// 按顺序复制所有条目和中央目录，并按条目在 `sink` 中的新位置更新卷号和偏移
+fn rewrite<R: Read + Seek>(input: &mut R, locate: Locate, sink: &mut impl Sink) -> Result<()> {
+    let directory = read_central_directory(input, locate)?;
+
+    // 相邻两个本地文件头之间的数据（含数据描述符）属于前一个条目
+    let mut order = Vec::with_capacity(directory.records.len());
+    for (index, record) in directory.records.iter().enumerate() {
+        let (disk, offset) = record.location()?;
+        order.push((locate(disk, offset)?, index));
+    }
+    order.sort_unstable();
+
+    let mut locations = vec![(0, 0); directory.records.len()];
+    for (i, &(start, index)) in order.iter().enumerate() {
+        let end = order.get(i + 1).map_or(directory.start, |next| next.0);
+        let mut header = [0; 30];
+        input.seek(SeekFrom::Start(start))?;
+        input.read_exact(&mut header)?;
+        if u32_at(&header, 0) != LOCAL_HEADER || end < start + 30 {
+            bail!("无效的本地文件头: {}", directory.records[index].name());
+        }
+
+        let header_len = 30 + u64::from(u16_at(&header, 26)) + u64::from(u16_at(&header, 28));
+        sink.keep_together(header_len)?;
+        locations[index] = sink.position();
+        sink.write_all(&header)?;
+        let copied = io::copy(&mut input.by_ref().take(end - start - 30), sink)?;
+        if copied != end - start - 30 {
+            bail!("条目数据不完整: {}", directory.records[index].name());
+        }
+    }
+
+    let records = directory
+        .records
+        .iter()
+        .zip(locations)
+        .map(|(record, (disk, offset))| record.relocate(disk, offset))
+        .collect::<Result<Vec<_>>>()?;
+    write_central_directory(sink, &records, &directory.comment, directory.zip64)
+}
+
+/// 写入目标，记录当前所在的分卷
+trait Sink: Write {
+    /// 当前写入位置：(卷号, 卷内偏移)
+    fn position(&self) -> (u32, u64);
+
+    /// 确保接下来的 `len` 字节写入同一卷中
+    fn keep_together(&mut self, len: u64) -> io::Result<()>;
+}
+
+/// 不分卷的写入目标，记录已写入的字节数
+struct Plain {
+    file: File,
+    written: u64,
+}
+
+impl Plain {
+    fn new(file: File) -> Self {
+        Self { file, written: 0 }
+    }
+
+    fn into_inner(self) -> File {
+        self.file
+    }
+}
+
+impl Write for Plain {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Sink for Plain {
+    fn position(&self) -> (u32, u64) {
+        (0, self.written)
+    }
+
+    fn keep_together(&mut self, _len: u64) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 将 (卷号, 卷内偏移) 转换为输入流中的偏移
+type Locate<'a> = &'a dyn Fn(u32, u64) -> Result<u64>;
+
+/// 中央目录和结尾记录中的信息
+struct CentralDirectory {
+    records: Vec<Record>,
+    /// 中央目录在输入流中的偏移
+    start: u64,
+    comment: Vec<u8>,
+    zip64: bool,
+}
+
+/// 查找结尾记录，返回其在流中的偏移和内容（含注释）
+fn find_end_record<R: Read + Seek>(input: &mut R) -> Result<(u64, Vec<u8>)> {
+    let len = input.seek(SeekFrom::End(0))?;
+    let tail_len = len.min(22 + 0xFFFF);
+    let mut tail = vec![0; tail_len as usize];
+    input.seek(SeekFrom::Start(len - tail_len))?;
+    input.read_exact(&mut tail)?;
+
+    let Some(index) = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|&i| u32_at(&tail, i) == END_OF_CENTRAL_DIRECTORY)
+    else {
+        bail!("找不到ZIP结尾记录");
+    };
+    Ok((len - tail_len + index as u64, tail.split_off(index)))
+}
+
+fn read_central_directory<R: Read + Seek>(
+    input: &mut R,
+    locate: Locate,
+) -> Result<CentralDirectory> {
+    let (end_offset, end) = find_end_record(input)?;
+    let comment_len = usize::from(u16_at(&end, 20)).min(end.len() - 22);
+    let comment = end[22..22 + comment_len].to_vec();
+
+    let mut cd_disk = u32::from(u16_at(&end, 6));
+    let mut total = u64::from(u16_at(&end, 10));
+    let mut cd_size = u64::from(u32_at(&end, 12));
+    let mut cd_offset = u64::from(u32_at(&end, 16));
+
+    // ZIP64 定位记录紧挨在结尾记录之前
+    let mut zip64 = false;
+    if end_offset >= 20 {
+        let mut locator = [0; 20];
+        input.seek(SeekFrom::Start(end_offset - 20))?;
+        input.read_exact(&mut locator)?;
+        if u32_at(&locator, 0) == ZIP64_LOCATOR {
+            let mut record = [0; 56];
+            input.seek(SeekFrom::Start(locate(
+                u32_at(&locator, 4),
+                u64_at(&locator, 8),
+            )?))?;
+            input.read_exact(&mut record)?;
+            if u32_at(&record, 0) != ZIP64_END_OF_CENTRAL_DIRECTORY {
+                bail!("无效的ZIP64结尾记录");
+            }
+            zip64 = true;
+            cd_disk = u32_at(&record, 20);
+            total = u64_at(&record, 32);
+            cd_size = u64_at(&record, 40);
+            cd_offset = u64_at(&record, 48);
+        }
+    }
+
+    let start = locate(cd_disk, cd_offset)?;
+    let mut data = Vec::new();
+    input.seek(SeekFrom::Start(start))?;
+    input.by_ref().take(cd_size).read_to_end(&mut data)?;
+
+    let mut records = Vec::new();
+    let mut position = 0;
+    for _ in 0..total {
+        let fixed = data
+            .get(position..position + 46)
+            .filter(|fixed| u32_at(fixed, 0) == CENTRAL_HEADER)
+            .context("无效的中央目录")?;
+        let len = 46
+            + usize::from(u16_at(fixed, 28))
+            + usize::from(u16_at(fixed, 30))
+            + usize::from(u16_at(fixed, 32));
+        let record = data
+            .get(position..position + len)
+            .context("中央目录不完整")?;
+        records.push(Record(record.to_vec()));
+        position += len;
+    }
+
+    Ok(CentralDirectory {
+        records,
+        start,
+        comment,
+        zip64,
+    })
+}
+
+/// 写入已更新位置的中央目录记录和结尾记录，需要时写入 ZIP64 结尾记录
+fn write_central_directory(
+    sink: &mut impl Sink,
+    records: &[Record],
+    comment: &[u8],
+    zip64: bool,
+) -> Result<()> {
+    let mut cd_start = None;
+    let mut cd_size = 0;
+    let mut disks = Vec::with_capacity(records.len());
+    for record in records {
+        sink.keep_together(record.0.len() as u64)?;
+        let position = sink.position();
+        cd_start.get_or_insert(position);
+        disks.push(position.0);
+        sink.write_all(&record.0)?;
+        cd_size += record.0.len() as u64;
+    }
+    let (cd_disk, cd_offset) = cd_start.unwrap_or_else(|| sink.position());
+
+    let total = records.len() as u64;
+    let zip64 = zip64
+        || total >= 0xFFFF
+        || cd_size >= 0xFFFF_FFFF
+        || cd_offset >= 0xFFFF_FFFF
+        || sink.position().0 >= 0xFFFF;
+    let end_len = 22 + comment.len() as u64 + if zip64 { 56 + 20 } else { 0 };
+    sink.keep_together(end_len)?;
+    let (disk, offset) = sink.position();
+    let on_disk = disks.iter().filter(|&&d| d == disk).count() as u64;
+
+    let mut end = Vec::new();
+    if zip64 {
+        end.extend(ZIP64_END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        end.extend(44u64.to_le_bytes());
+        end.extend(45u16.to_le_bytes());
+        end.extend(45u16.to_le_bytes());
+        end.extend(disk.to_le_bytes());
+        end.extend(cd_disk.to_le_bytes());
+        end.extend(on_disk.to_le_bytes());
+        end.extend(total.to_le_bytes());
+        end.extend(cd_size.to_le_bytes());
+        end.extend(cd_offset.to_le_bytes());
+
+        end.extend(ZIP64_LOCATOR.to_le_bytes());
+        end.extend(disk.to_le_bytes());
+        end.extend(offset.to_le_bytes());
+        end.extend((disk + 1).to_le_bytes());
+    }
+    end.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    end.extend(clamp_u16(disk.into()).to_le_bytes());
+    end.extend(clamp_u16(cd_disk.into()).to_le_bytes());
+    end.extend(clamp_u16(on_disk).to_le_bytes());
+    end.extend(clamp_u16(total).to_le_bytes());
+    end.extend(clamp_u32(cd_size).to_le_bytes());
+    end.extend(clamp_u32(cd_offset).to_le_bytes());
+    end.extend((comment.len() as u16).to_le_bytes());
+    end.extend(comment);
+    sink.write_all(&end)?;
+    sink.flush()?;
+    Ok(())
+}
+
+/// 解析额外字段，返回 (标识, 完整字段)
+fn extra_fields(extra: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut fields = Vec::new();
+    let mut position = 0;
+    while position + 4 <= extra.len() {
+        let len = 4 + usize::from(u16_at(extra, position + 2));
+        let Some(field) = extra.get(position..position + len) else {
+            break;
+        };
+        fields.push((u16_at(field, 0), field));
+        position += len;
+    }
+    fields
+}
+
+/// 中央目录中的一条文件记录
+struct Record(Vec<u8>);
+
+impl Record {
+    fn name_len(&self) -> usize {
+        usize::from(u16_at(&self.0, 28))
+    }
+
+    fn extra_len(&self) -> usize {
+        usize::from(u16_at(&self.0, 30))
+    }
+
+    fn name(&self) -> String {
+        String::from_utf8_lossy(&self.0[46..46 + self.name_len()]).into_owned()
+    }
+
+    /// 额外字段，返回 (标识, 完整字段)
+    fn extra_fields(&self) -> Vec<(u16, &[u8])> {
+        let start = 46 + self.name_len();
+        extra_fields(&self.0[start..start + self.extra_len()])
+    }
+
+    /// ZIP64 额外字段中的原始大小、压缩后大小、本地文件头偏移和卷号
+    fn zip64(&self) -> Result<[Option<u64>; 4]> {
+        let Some((_, field)) = self
+            .extra_fields()
+            .into_iter()
+            .find(|(id, _)| *id == 0x0001)
+        else {
+            return Ok([None; 4]);
+        };
+        let data = &field[4..];
+
+        // 按规范只包含文件头中为最大值的字段，zip 库在 large_file 时总是写入两个大小
+        let marked = [
+            u32_at(&self.0, 24) == 0xFFFF_FFFF,
+            u32_at(&self.0, 20) == 0xFFFF_FFFF,
+            u32_at(&self.0, 42) == 0xFFFF_FFFF,
+            u16_at(&self.0, 34) == 0xFFFF,
+        ];
+        let marked_len: usize = marked
+            .iter()
+            .zip([8, 8, 8, 4])
+            .filter(|(marked, _)| **marked)
+            .map(|(_, len)| len)
+            .sum();
+        let present = if data.len() == marked_len {
+            marked
+        } else {
+            [
+                data.len() >= 16,
+                data.len() >= 16,
+                data.len() >= 24,
+                data.len() >= 28,
+            ]
+        };
+
+        let mut values = [None; 4];
+        let mut position = 0;
+        for (i, value) in values.iter_mut().enumerate() {
+            if !present[i] {
+                continue;
+            }
+            let len = if i == 3 { 4 } else { 8 };
+            let bytes = data
+                .get(position..position + len)
+                .context("无效的ZIP64额外字段")?;
+            *value = Some(if i == 3 {
+                u64::from(u32_at(bytes, 0))
+            } else {
+                u64_at(bytes, 0)
+            });
+            position += len;
+        }
+        Ok(values)
+    }
+
+    /// 本地文件头所在的 (卷号, 卷内偏移)
+    fn location(&self) -> Result<(u32, u64)> {
+        let zip64 = self.zip64()?;
+        let disk = zip64[3].map_or(u32::from(u16_at(&self.0, 34)), |disk| disk as u32);
+        let offset = zip64[2].unwrap_or(u64::from(u32_at(&self.0, 42)));
+        Ok((disk, offset))
+    }
+
+    /// 更新卷号和本地文件头偏移，超出范围的值写入 ZIP64 额外字段
+    fn relocate(&self, disk: u32, offset: u64) -> Result<Record> {
+        let mut zip64 = self.zip64()?;
+        zip64[2] = (zip64[2].is_some() || offset >= 0xFFFF_FFFF).then_some(offset);
+        zip64[3] = (zip64[3].is_some() || disk >= 0xFFFF).then_some(u64::from(disk));
+
+        // 额外字段中存在的值在文件头中标记为最大值
+        let mut header = self.0[..46].to_vec();
+        if zip64[0].is_some() {
+            header[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+        if zip64[1].is_some() {
+            header[20..24].copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+        header[42..46]
+            .copy_from_slice(&clamp_u32(zip64[2].map_or(offset, |_| u64::MAX)).to_le_bytes());
+        header[34..36].copy_from_slice(
+            &clamp_u16(zip64[3].map_or(u64::from(disk), |_| u64::MAX)).to_le_bytes(),
+        );
+
+        let mut extra = Vec::new();
+        if zip64.iter().any(Option::is_some) {
+            let mut data = Vec::new();
+            for (i, value) in zip64.iter().enumerate() {
+                match value {
+                    Some(value) if i == 3 => data.extend((*value as u32).to_le_bytes()),
+                    Some(value) => data.extend(value.to_le_bytes()),
+                    None => {}
+                }
+            }
+            extra.extend(0x0001u16.to_le_bytes());
+            extra.extend((data.len() as u16).to_le_bytes());
+            extra.extend(data);
+        }
+        for (id, field) in self.extra_fields() {
+            if id != 0x0001 {
+                extra.extend(field);
+            }
+        }
+        let extra_len = u16::try_from(extra.len()).context("额外字段过长")?;
+        header[30..32].copy_from_slice(&extra_len.to_le_bytes());
+
+        let name_end = 46 + self.name_len();
+        let mut record = header;
+        record.extend(&self.0[46..name_end]);
+        record.extend(extra);
+        record.extend(&self.0[name_end + self.extra_len()..]);
+        Ok(Record(record))
+    }
+}
+
+fn u16_at(data: &[u8], position: usize) -> u16 {
+    u16::from_le_bytes(data[position..position + 2].try_into().unwrap())
+}
+
+fn u32_at(data: &[u8], position: usize) -> u32 {
+    u32::from_le_bytes(data[position..position + 4].try_into().unwrap())
+}
+
+fn u64_at(data: &[u8], position: usize) -> u64 {
+    u64::from_le_bytes(data[position..position + 8].try_into().unwrap())
+}
+
+fn clamp_u16(value: u64) -> u16 {
+    value.min(0xFFFF) as u16
+}
+
+fn clamp_u32(value: u64) -> u32 {
+    value.min(0xFFFF_FFFF) as u32
+}
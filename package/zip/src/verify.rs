@@ -0,0 +1,280 @@
+use anyhow::{bail, Context, Result};
+use bzip2::read::BzDecoder;
+use flate2::read::GzDecoder;
+use log::{debug, error};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use xz2::read::XzDecoder;
+use zip::result::ZipError;
+use zip::{CompressionMethod, ZipArchive};
+
+use crate::archive::ArchiveFormat;
+use crate::split::open_archive;
+
+/// 检查结果
+#[derive(Debug)]
+pub struct Report {
+    /// 检查的条目数
+    pub entries: usize,
+    /// 损坏的条目名称，原因已输出到日志
+    pub damaged: Vec<String>,
+}
+
+/// 检查压缩包的完整性，分卷压缩包会先合并，`format` 为 None 时根据扩展名推断
+pub fn test_path(
+    archive: &Path,
+    format: Option<ArchiveFormat>,
+    password: impl FnOnce() -> Result<String>,
+) -> Result<Report> {
+    // 编号分卷去掉 .001 等编号后再推断格式
+    let format = format
+        .or_else(|| ArchiveFormat::from_path(archive))
+        .or_else(|| ArchiveFormat::from_path(&archive.with_extension("")))
+        .unwrap_or(ArchiveFormat::Zip);
+    let file = open_archive(archive)?;
+    test_archive(file, format, password)
+        .with_context(|| format!("无法检查压缩包: {}", archive.display()))
+}
+
+/// 读取所有条目并校验数据
+///
+/// ZIP 格式检查中央目录与本地文件头是否一致，并校验每个条目的 CRC32 和大小，
+/// 损坏的条目记录在结果中并继续检查其余条目。tar 格式只能顺序读取，遇到损坏的数据时直接返回错误。
+/// `password` 只在包含加密条目时调用。
+fn test_archive(
+    file: File,
+    format: ArchiveFormat,
+    password: impl FnOnce() -> Result<String>,
+) -> Result<Report> {
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::Zip => return test_zip(file, password),
+        ArchiveFormat::Tar => Box::new(BufReader::new(file)),
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(BufReader::new(file))),
+        ArchiveFormat::TarZst => Box::new(zstd::Decoder::new(file)?),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(BufReader::new(file))),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(BufReader::new(file))),
+    };
+    test_tar(reader)
+}
+
+fn test_zip(file: File, password: impl FnOnce() -> Result<String>) -> Result<Report> {
+    let mut headers = file.try_clone()?;
+    let mut zip = ZipArchive::new(file).context("无效的ZIP文件")?;
+    let mut damaged = check_local_headers(&mut zip, &mut headers)?;
+    let bad_headers: HashSet<_> = damaged.iter().cloned().collect();
+
+    let mut encrypted = false;
+    for i in 0..zip.len() {
+        if !bad_headers.contains(zip.name_for_index(i).unwrap_or_default()) {
+            encrypted |= zip.by_index_raw(i)?.encrypted();
+        }
+    }
+    let password = if encrypted { Some(password()?) } else { None };
+
+    for i in 0..zip.len() {
+        let name = zip.name_for_index(i).unwrap_or_default().to_string();
+        // 文件头已损坏的条目无法正确读取
+        if bad_headers.contains(&name) {
+            continue;
+        }
+
+        let entry = match &password {
+            Some(password) if zip.by_index_raw(i)?.encrypted() => {
+                match zip.by_index_decrypt(i, password.as_bytes()) {
+                    Err(ZipError::InvalidPassword) => bail!("密码错误"),
+                    result => result,
+                }
+            }
+            _ => zip.by_index(i),
+        };
+        // zip 库在读取到结尾时校验 CRC32
+        let result = entry.map_err(anyhow::Error::from).and_then(|mut entry| {
+            let read = io::copy(&mut entry, &mut io::sink())?;
+            if read != entry.size() {
+                bail!("大小不一致：应为 {}，实际为 {}", entry.size(), read);
+            }
+            Ok(())
+        });
+        match result {
+            Ok(()) => debug!("OK: {}", name),
+            Err(e) => damaged.push(report_damaged(name, format!("数据损坏: {:#}", e))),
+        }
+    }
+
+    Ok(Report {
+        entries: zip.len(),
+        damaged,
+    })
+}
+
+/// 中央目录中与本地文件头对应的字段
+struct Entry {
+    name: String,
+    header_start: u64,
+    data_start: u64,
+    compression: CompressionMethod,
+    encrypted: bool,
+    crc32: u32,
+    compressed_size: u64,
+    size: u64,
+}
+
+/// 检查每个条目的本地文件头是否与中央目录一致，数据是否超出范围
+fn check_local_headers(zip: &mut ZipArchive<File>, file: &mut File) -> Result<Vec<String>> {
+    let mut damaged = Vec::new();
+    let mut entries = Vec::with_capacity(zip.len());
+    for i in 0..zip.len() {
+        let name = zip.name_for_index(i).unwrap_or_default().to_string();
+        // zip 库打开条目时会检查本地文件头的标记
+        let entry = match zip.by_index_raw(i) {
+            Ok(entry) => entry,
+            Err(e) => {
+                damaged.push(report_damaged(name, format!("无效的本地文件头: {}", e)));
+                continue;
+            }
+        };
+        entries.push(Entry {
+            name,
+            header_start: entry.header_start(),
+            data_start: entry.data_start(),
+            compression: entry.compression(),
+            encrypted: entry.encrypted(),
+            crc32: entry.crc32(),
+            compressed_size: entry.compressed_size(),
+            size: entry.size(),
+        });
+    }
+
+    // 条目的数据不能越过下一个条目的文件头或中央目录
+    let directory_start = zip.central_directory_start();
+    let mut starts: Vec<_> = entries.iter().map(|entry| entry.header_start).collect();
+    starts.sort_unstable();
+
+    for entry in &entries {
+        if let Err(e) = check_local_header(file, entry, &starts, directory_start) {
+            damaged.push(report_damaged(entry.name.clone(), format!("{:#}", e)));
+        }
+    }
+    Ok(damaged)
+}
+
+fn check_local_header(
+    file: &mut File,
+    entry: &Entry,
+    starts: &[u64],
+    directory_start: u64,
+) -> Result<()> {
+    let local = read_local_header(file, entry.header_start)?;
+    if local.name != entry.name.as_bytes() {
+        bail!("本地文件头中的名称与中央目录不一致");
+    }
+    // AES 加密的条目在文件头中记录为方法 99，实际的压缩方法位于额外字段中
+    #[allow(deprecated)]
+    let method = CompressionMethod::from_u16(local.method);
+    if method != entry.compression && !(entry.encrypted && method == CompressionMethod::Aes) {
+        bail!("本地文件头中的压缩方法与中央目录不一致");
+    }
+    if (local.flags & 0x0001 != 0) != entry.encrypted {
+        bail!("本地文件头中的加密标志与中央目录不一致");
+    }
+
+    // 使用数据描述符时，本地文件头中的 CRC32 和大小为 0
+    if local.flags & 0x0008 == 0 {
+        if local.crc32 != entry.crc32 {
+            bail!("本地文件头中的 CRC32 与中央目录不一致");
+        }
+        if (local.compressed_size, local.size) != (entry.compressed_size, entry.size) {
+            bail!("本地文件头中的大小与中央目录不一致");
+        }
+    }
+
+    let next = starts
+        .iter()
+        .find(|&&start| start > entry.header_start)
+        .map_or(directory_start, |&start| start.min(directory_start));
+    if entry.data_start + entry.compressed_size > next {
+        bail!("条目数据不完整，压缩包可能被截断");
+    }
+    Ok(())
+}
+
+/// 本地文件头中的信息
+struct LocalHeader {
+    name: Vec<u8>,
+    flags: u16,
+    method: u16,
+    crc32: u32,
+    compressed_size: u64,
+    size: u64,
+}
+
+/// 读取位于 `offset` 的本地文件头
+fn read_local_header(file: &mut File, offset: u64) -> Result<LocalHeader> {
+    let mut fixed = [0; 30];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut fixed).context("本地文件头不完整")?;
+    let u16_at = |at: usize| u16::from_le_bytes([fixed[at], fixed[at + 1]]);
+    let u32_at = |at: usize| u32::from_le_bytes(fixed[at..at + 4].try_into().unwrap());
+    let name_len = usize::from(u16_at(26));
+    let mut rest = vec![0; name_len + usize::from(u16_at(28))];
+    file.read_exact(&mut rest).context("本地文件头不完整")?;
+
+    let mut compressed_size = u64::from(u32_at(18));
+    let mut size = u64::from(u32_at(22));
+    // 大小为最大值时，实际的值位于 ZIP64 额外字段中：原始大小在前，压缩后大小在后
+    if compressed_size == 0xFFFF_FFFF || size == 0xFFFF_FFFF {
+        let mut extra = &rest[name_len..];
+        while extra.len() >= 4 {
+            let id = u16::from_le_bytes([extra[0], extra[1]]);
+            let len = 4 + usize::from(u16::from_le_bytes([extra[2], extra[3]]));
+            let Some(field) = extra.get(4..len) else {
+                break;
+            };
+            if id == 0x0001 && field.len() >= 16 {
+                size = u64::from_le_bytes(field[..8].try_into().unwrap());
+                compressed_size = u64::from_le_bytes(field[8..16].try_into().unwrap());
+                break;
+            }
+            extra = &extra[len..];
+        }
+    }
+
+    Ok(LocalHeader {
+        name: rest[..name_len].to_vec(),
+        flags: u16_at(6),
+        method: u16_at(8),
+        crc32: u32_at(14),
+        compressed_size,
+        size,
+    })
+}
+
+fn test_tar(reader: Box<dyn Read>) -> Result<Report> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = 0;
+    for entry in archive.entries().context("无效的tar文件")? {
+        let mut entry = entry.context("无效的tar条目")?;
+        let name = entry.path()?.display().to_string();
+        let read =
+            io::copy(&mut entry, &mut io::sink()).with_context(|| format!("数据损坏: {}", name))?;
+        if read != entry.size() {
+            bail!("数据不完整: {}", name);
+        }
+        debug!("OK: {}", name);
+        entries += 1;
+    }
+    // 读完结尾的填充，外层压缩在数据结束时校验 CRC
+    io::copy(&mut archive.into_inner(), &mut io::sink()).context("压缩数据损坏")?;
+
+    Ok(Report {
+        entries,
+        damaged: Vec::new(),
+    })
+}
+
+fn report_damaged(name: String, message: String) -> String {
+    error!("{}: {}", name, message);
+    name
+}
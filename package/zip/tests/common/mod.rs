@@ -0,0 +1,19 @@
+//! 集成测试共用的辅助函数
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// 运行 ziper 的命令，清除会影响结果的环境变量
+pub fn command() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ziper"));
+    command.env_remove("ZIPER_PASSWORD");
+    command
+}
+
+pub fn ziper(args: &[&str]) -> Output {
+    command().args(args).output().unwrap()
+}
+
+pub fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
@@ -0,0 +1,759 @@
+mod common;
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+use common::{command, path_str, ziper};
+
+/// 读取 tar 包中的条目，返回 (路径, 类型, 权限, 链接目标)
+fn tar_entries(reader: impl Read) -> Vec<(String, tar::EntryType, u32, Option<String>)> {
+    let mut archive = tar::Archive::new(reader);
+    archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let header = entry.header();
+            (
+                entry.path().unwrap().to_string_lossy().into_owned(),
+                header.entry_type(),
+                header.mode().unwrap() & 0o777,
+                header
+                    .link_name()
+                    .unwrap()
+                    .map(|link| link.to_string_lossy().into_owned()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn infers_format_from_extension() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source
+        .child("index.html")
+        .write_str("<html></html>")
+        .unwrap();
+
+    let archive = temp.child("dist.tar.gz");
+    let output = ziper(&[path_str(source.path()), path_str(archive.path())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let file = File::open(archive.path()).unwrap();
+    let entries = tar_entries(flate2::read::GzDecoder::new(file));
+    let names: Vec<&str> = entries.iter().map(|e| e.0.as_str()).collect();
+    assert_eq!(names, ["dist", "dist/index.html"]);
+}
+
+#[test]
+fn format_flag_overrides_extension() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("a.txt").write_str("a").unwrap();
+
+    let archive = temp.child("dist.bin");
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--format",
+        "tar.zst",
+    ]);
+    assert!(output.status.success());
+
+    let file = File::open(archive.path()).unwrap();
+    let entries = tar_entries(zstd::Decoder::new(file).unwrap());
+    assert_eq!(entries.len(), 2);
+
+    // 未指定输出时使用格式对应的扩展名
+    let output = ziper(&[path_str(source.path()), "-f", "tar.xz"]);
+    assert!(output.status.success());
+    temp.child("dist.tar.xz").assert(predicates::path::exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn tar_preserves_modes_and_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("run.sh").write_str("#!/bin/sh").unwrap();
+    source.child("config.toml").write_str("key = 1").unwrap();
+    set_mode(source.child("run.sh").path(), 0o750);
+    set_mode(source.child("config.toml").path(), 0o600);
+    set_mode(source.path(), 0o755);
+    symlink("run.sh", source.child("start").path()).unwrap();
+
+    let archive = temp.child("dist.tar");
+    let output = ziper(&[path_str(source.path()), path_str(archive.path())]);
+    assert!(output.status.success());
+
+    let mut entries = tar_entries(File::open(archive.path()).unwrap());
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        entries,
+        [
+            ("dist".to_string(), tar::EntryType::Directory, 0o755, None),
+            (
+                "dist/config.toml".to_string(),
+                tar::EntryType::Regular,
+                0o600,
+                None
+            ),
+            (
+                "dist/run.sh".to_string(),
+                tar::EntryType::Regular,
+                0o750,
+                None
+            ),
+            (
+                "dist/start".to_string(),
+                tar::EntryType::Symlink,
+                0o777,
+                Some("run.sh".to_string())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn compression_method_and_auto_store() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source
+        .child("app.js")
+        .write_str(&"let a = 1;\n".repeat(200))
+        .unwrap();
+    source
+        .child("logo.PNG")
+        .write_str(&"png".repeat(200))
+        .unwrap();
+
+    let archive = temp.child("dist.zip");
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--method",
+        "zstd",
+        "--level",
+        "19",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+    let script = zip.by_name("dist/app.js").unwrap();
+    assert_eq!(script.compression(), zip::CompressionMethod::Zstd);
+    assert!(script.compressed_size() < script.size());
+    drop(script);
+    let logo = zip.by_name("dist/logo.PNG").unwrap();
+    assert_eq!(logo.compression(), zip::CompressionMethod::Stored);
+}
+
+#[test]
+fn rejects_invalid_level() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("a.txt").write_str("a").unwrap();
+    let archive = temp.child("dist.zip");
+
+    for args in [
+        ["--method", "deflate", "--level", "10"],
+        ["--method", "store", "--level", "1"],
+    ] {
+        let mut all = vec![path_str(source.path()), path_str(archive.path())];
+        all.extend(args);
+        assert!(!ziper(&all).status.success());
+    }
+    archive.assert(predicates::path::missing());
+
+    // tar 格式只能设置外层压缩的级别
+    let tarball = temp.child("dist.tar.gz");
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(tarball.path()),
+        "--method",
+        "zstd",
+    ]);
+    assert!(!output.status.success());
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(tarball.path()),
+        "--level",
+        "9",
+    ]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn rejects_lzma_method() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("a.txt").write_str("a").unwrap();
+    let archive = temp.child("dist.zip");
+
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--method",
+        "lzma",
+    ]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("不支持 lzma 压缩方法"));
+    archive.assert(predicates::path::missing());
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+}
+
+#[cfg(unix)]
+fn mode_of(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[cfg(unix)]
+#[test]
+fn modes_survive_round_trip() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("bin/run.sh").write_str("#!/bin/sh").unwrap();
+    source.child("config.toml").write_str("key = 1").unwrap();
+    source.child("secret.key").write_str("secret").unwrap();
+    set_mode(source.child("bin/run.sh").path(), 0o750);
+    set_mode(source.child("config.toml").path(), 0o644);
+    set_mode(source.child("secret.key").path(), 0o600);
+
+    // 奇数秒，DOS 时间只能精确到 2 秒
+    let mtime = UNIX_EPOCH + Duration::from_secs(1577934247);
+    File::options()
+        .write(true)
+        .open(source.child("config.toml").path())
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+
+    let archive = temp.child("dist.zip");
+    let output = ziper(&[path_str(source.path()), path_str(archive.path())]);
+    assert!(output.status.success());
+
+    let out = temp.child("out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(mode_of(out.child("dist/bin/run.sh").path()), 0o750);
+    assert_eq!(mode_of(out.child("dist/config.toml").path()), 0o644);
+    assert_eq!(mode_of(out.child("dist/secret.key").path()), 0o600);
+    let config = std::fs::metadata(out.child("dist/config.toml").path()).unwrap();
+    assert_eq!(config.modified().unwrap(), mtime);
+}
+
+#[cfg(unix)]
+#[test]
+fn normalize_permissions() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("run.sh").write_str("#!/bin/sh").unwrap();
+    source.child("secret.key").write_str("secret").unwrap();
+    set_mode(source.child("run.sh").path(), 0o700);
+    set_mode(source.child("secret.key").path(), 0o600);
+
+    let archive = temp.child("dist.zip");
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--normalize-permissions",
+    ]);
+    assert!(output.status.success());
+
+    let mut zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+    let mode = |zip: &mut zip::ZipArchive<File>, name| {
+        zip.by_name(name).unwrap().unix_mode().unwrap() & 0o777
+    };
+    assert_eq!(mode(&mut zip, "dist/run.sh"), 0o755);
+    assert_eq!(mode(&mut zip, "dist/secret.key"), 0o644);
+}
+
+#[cfg(unix)]
+#[test]
+fn stores_empty_directories_and_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("cache").create_dir_all().unwrap();
+    source.child("lib/libfoo.so.1").write_str("elf").unwrap();
+    symlink("libfoo.so.1", source.child("lib/libfoo.so").path()).unwrap();
+    symlink("lib", source.child("current").path()).unwrap();
+
+    let archive = temp.child("dist.zip");
+    let output = ziper(&[path_str(source.path()), path_str(archive.path())]);
+    assert!(output.status.success());
+
+    let out = temp.child("out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(out.child("dist/cache").path().is_dir());
+    assert_eq!(
+        std::fs::read_link(out.child("dist/lib/libfoo.so").path()).unwrap(),
+        Path::new("libfoo.so.1")
+    );
+    assert_eq!(
+        std::fs::read_link(out.child("dist/current").path()).unwrap(),
+        Path::new("lib")
+    );
+    out.child("dist/current/libfoo.so").assert("elf");
+}
+
+#[cfg(unix)]
+#[test]
+fn follow_symlinks_skips_loops() {
+    use std::os::unix::fs::symlink;
+
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("lib/a.txt").write_str("a").unwrap();
+    symlink("lib", source.child("current").path()).unwrap();
+    symlink("..", source.child("lib/parent").path()).unwrap();
+
+    let archive = temp.child("dist.zip");
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--follow-symlinks",
+    ]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("symlink loop"));
+
+    let mut zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "dist/",
+            "dist/current/",
+            "dist/current/a.txt",
+            "dist/lib/",
+            "dist/lib/a.txt"
+        ]
+    );
+    assert!(!zip.by_name("dist/current/a.txt").unwrap().is_symlink());
+}
+
+/// 压缩并返回排序后的条目名称
+fn zip_names(source: &Path, archive: &Path, extra: &[&str]) -> Vec<String> {
+    let mut args = vec![path_str(source), path_str(archive)];
+    args.extend(extra);
+    let output = ziper(&args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let zip = zip::ZipArchive::new(File::open(archive).unwrap()).unwrap();
+    let mut names: Vec<String> = zip.file_names().map(String::from).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn honours_ignore_files() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("app");
+    source
+        .child(".gitignore")
+        .write_str("*.log\n!keep.log\n/build\ncache/\n")
+        .unwrap();
+    source.child(".zipignore").write_str("*.map\n").unwrap();
+    source.child("a.log").write_str("").unwrap();
+    source.child("keep.log").write_str("").unwrap();
+    source.child("build/out.js").write_str("").unwrap();
+    source.child("src/build/gen.js").write_str("").unwrap();
+    source.child("src/cache/x").write_str("").unwrap();
+    source.child("src/cache.txt").write_str("").unwrap();
+    source.child("main.js.map").write_str("").unwrap();
+
+    let names = zip_names(source.path(), temp.child("a.zip").path(), &[]);
+    assert_eq!(
+        names,
+        [
+            "app/",
+            "app/.gitignore",
+            "app/.zipignore",
+            "app/keep.log",
+            "app/src/",
+            "app/src/build/",
+            "app/src/build/gen.js",
+            "app/src/cache.txt",
+        ]
+    );
+
+    // --no-vcs-ignore 只保留 .zipignore
+    let names = zip_names(
+        source.path(),
+        temp.child("b.zip").path(),
+        &["--no-vcs-ignore"],
+    );
+    assert!(names.contains(&"app/a.log".to_string()));
+    assert!(names.contains(&"app/build/out.js".to_string()));
+    assert!(names.contains(&"app/src/cache/x".to_string()));
+    assert!(!names.contains(&"app/main.js.map".to_string()));
+}
+
+#[test]
+fn ignore_patterns_match_relative_paths() {
+    let temp = TempDir::new().unwrap();
+    // 源目录之外名为 build 的上级目录不影响匹配
+    let source = temp.child("build/app");
+    source.child("src/a.js").write_str("").unwrap();
+    source.child("src/a.css").write_str("").unwrap();
+    source.child("src/lib/b.js").write_str("").unwrap();
+    source.child("lib/build/c.txt").write_str("").unwrap();
+    source
+        .child("test/__snapshots__/a.snap")
+        .write_str("")
+        .unwrap();
+    source
+        .child("node_modules/x/index.js")
+        .write_str("")
+        .unwrap();
+    source
+        .child("node_modules/y/index.js")
+        .write_str("")
+        .unwrap();
+
+    let archive = temp.child("app.zip");
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--ignore",
+        "src/*.js,build,**/*.snap,node_modules",
+    ]);
+    assert!(output.status.success());
+    // 被忽略的目录被剪除，其中的文件不再逐个访问
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("Ignoring:").count(), 4, "{}", stderr);
+
+    let zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "app/",
+            "app/lib/",
+            "app/src/",
+            "app/src/a.css",
+            "app/src/lib/",
+            "app/src/lib/b.js",
+            "app/test/",
+            "app/test/__snapshots__/",
+        ]
+    );
+}
+
+#[test]
+fn include_whitelist() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("site");
+    source.child("index.html").write_str("").unwrap();
+    source.child("notes.md").write_str("").unwrap();
+    source.child("docs/guide.html").write_str("").unwrap();
+    source.child("docs/draft.md").write_str("").unwrap();
+    source.child("assets/img/logo.svg").write_str("").unwrap();
+    source.child("assets/fonts").create_dir_all().unwrap();
+    source.child("assets/tmp.log").write_str("").unwrap();
+
+    let names = zip_names(
+        source.path(),
+        temp.child("site.zip").path(),
+        &["--include", "*.html,assets", "--ignore", "*.log"],
+    );
+    assert_eq!(
+        names,
+        [
+            "site/",
+            "site/assets/",
+            "site/assets/fonts/",
+            "site/assets/img/",
+            "site/assets/img/logo.svg",
+            "site/docs/guide.html",
+            "site/index.html",
+        ]
+    );
+}
+
+/// 目录中残留的临时文件
+fn temp_files(dir: &Path) -> Vec<String> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect()
+}
+
+#[test]
+fn skips_output_inside_source() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("app");
+    source.child("index.js").write_str("").unwrap();
+    let archive = source.child("app.zip");
+
+    // 第二次运行时输出文件已经存在于源目录中
+    for _ in 0..2 {
+        let names = zip_names(source.path(), archive.path(), &[]);
+        assert_eq!(names, ["app/", "app/index.js"]);
+    }
+    assert!(temp_files(source.path()).is_empty());
+}
+
+#[test]
+fn removes_partial_output_on_failure() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("app");
+    source.child("index.js").write_str("").unwrap();
+    // 输出路径是已存在的目录，写入完成后的重命名会失败
+    let output = temp.child("out.zip");
+    output.child("keep").write_str("").unwrap();
+
+    let result = ziper(&[path_str(source.path()), path_str(output.path())]);
+    assert!(!result.status.success());
+    output.child("keep").assert(predicates::path::exists());
+    assert!(temp_files(temp.path()).is_empty());
+}
+
+#[test]
+fn output_is_independent_of_jobs() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("app");
+    for i in 0..50 {
+        source
+            .child(format!("dir{}/file{}.txt", i % 5, i))
+            .write_str(&format!("content {}\n", i).repeat(i * 100))
+            .unwrap();
+    }
+    source
+        .child("image.png")
+        .write_binary(&[0x89, b'P', b'N', b'G'])
+        .unwrap();
+
+    let archives: Vec<Vec<u8>> = ["1", "8"]
+        .into_iter()
+        .map(|jobs| {
+            let archive = temp.child(format!("app-{}.zip", jobs));
+            let output = ziper(&[
+                path_str(source.path()),
+                path_str(archive.path()),
+                "--jobs",
+                jobs,
+            ]);
+            assert!(output.status.success());
+            std::fs::read(archive.path()).unwrap()
+        })
+        .collect();
+    assert_eq!(archives[0], archives[1]);
+
+    let names = zip_names(
+        source.path(),
+        temp.child("app.zip").path(),
+        &["--jobs", "4"],
+    );
+    assert_eq!(names.len(), 1 + 5 + 51);
+}
+
+/// 修改源文件的修改时间和权限，内容不变
+#[cfg(unix)]
+fn touch_tree(dir: &Path, mode: u32) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            touch_tree(&path, mode);
+        } else {
+            set_mode(&path, mode);
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(std::time::SystemTime::now())
+                .unwrap();
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn reproducible_archives_are_identical() {
+    use std::hash::{DefaultHasher, Hasher};
+    use std::os::unix::fs::symlink;
+
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source
+        .child("index.html")
+        .write_str("<html></html>")
+        .unwrap();
+    source.child("js/main.js").write_str("main()").unwrap();
+    source.child("empty").create_dir_all().unwrap();
+    symlink("index.html", source.child("home.html").path()).unwrap();
+
+    let hash = |path: &Path| {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&std::fs::read(path).unwrap());
+        hasher.finish()
+    };
+
+    for format in ["zip", "tar.gz", "tar.zst"] {
+        let mut hashes = Vec::new();
+        for (run, mode) in [0o644, 0o600].into_iter().enumerate() {
+            touch_tree(source.path(), mode);
+            let archive = temp.child(format!("{}.{}", run, format));
+            let output = ziper(&[
+                path_str(source.path()),
+                path_str(archive.path()),
+                "--reproducible",
+            ]);
+            assert!(output.status.success());
+            hashes.push(hash(archive.path()));
+        }
+        assert_eq!(hashes[0], hashes[1], "{}", format);
+    }
+}
+
+#[test]
+fn reproducible_clamps_to_source_date_epoch() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.child("new.txt").write_str("new").unwrap();
+    let old = source.child("old.txt");
+    old.write_str("old").unwrap();
+    File::options()
+        .write(true)
+        .open(old.path())
+        .unwrap()
+        .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_500_000_000))
+        .unwrap();
+
+    let archive = temp.child("dist.zip");
+    let output = command()
+        .args([
+            path_str(source.path()),
+            path_str(archive.path()),
+            "--reproducible",
+        ])
+        .env("SOURCE_DATE_EPOCH", "1600000000")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let mut zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+    for (name, expected) in [
+        ("dist/new.txt", (2020, 9, 13, 12, 26, 40)),
+        ("dist/old.txt", (2017, 7, 14, 2, 40, 0)),
+    ] {
+        let entry = zip.by_name(name).unwrap();
+        let time = entry.last_modified().unwrap();
+        assert_eq!(
+            (
+                time.year(),
+                time.month(),
+                time.day(),
+                time.hour(),
+                time.minute(),
+                time.second()
+            ),
+            expected,
+            "{}",
+            name
+        );
+        assert!(entry.extra_data().is_none_or(|data| data.is_empty()));
+        assert_eq!(entry.unix_mode().unwrap() & 0o777, 0o644);
+    }
+}
+
+/// 在 `dir` 中运行 ziper，压缩与子命令同名的目录 `name`
+fn compress_named_like_subcommand(dir: &TempDir, name: &str, extra: &[&str]) {
+    let source = dir.child(name);
+    source.child("a.txt").write_str("a").unwrap();
+
+    let output = command()
+        .current_dir(dir.path())
+        .arg(name)
+        .args(extra)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let archive = File::open(dir.child(format!("{}.zip", name)).path()).unwrap();
+    let zip = zip::ZipArchive::new(archive).unwrap();
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(names, [format!("{}/", name), format!("{}/a.txt", name)]);
+}
+
+#[test]
+fn compresses_directories_named_extract() {
+    let temp = TempDir::new().unwrap();
+    compress_named_like_subcommand(&temp, "extract", &[]);
+    compress_named_like_subcommand(&temp, "x", &["-q"]);
+
+    // 能按子命令解析时仍然解压
+    let out = temp.child("out");
+    let output = ziper(&[
+        "x",
+        path_str(temp.child("x.zip").path()),
+        path_str(out.path()),
+    ]);
+    assert!(output.status.success());
+    out.child("x/a.txt").assert("a");
+}
+
+#[test]
+fn compresses_directories_named_list() {
+    let temp = TempDir::new().unwrap();
+    compress_named_like_subcommand(&temp, "list", &[]);
+    compress_named_like_subcommand(&temp, "l", &["--method", "store"]);
+
+    let output = ziper(&["l", path_str(temp.child("l.zip").path())]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("l/a.txt"));
+}
+
+#[test]
+fn compresses_directories_named_test() {
+    let temp = TempDir::new().unwrap();
+    compress_named_like_subcommand(&temp, "test", &["-v"]);
+
+    let output = ziper(&["test", path_str(temp.child("test.zip").path())]);
+    assert!(output.status.success());
+}
@@ -0,0 +1,349 @@
+mod common;
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use zip::write::SimpleFileOptions;
+use zip::{DateTime, ZipWriter};
+
+use common::{command, path_str, ziper};
+
+/// 使用给定的条目构造 ZIP 文件，条目为 (名称, 内容)
+fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    for (name, content) in entries {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn round_trip_restores_content() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source
+        .child("index.html")
+        .write_str("<html></html>")
+        .unwrap();
+    source.child("css/style.css").write_str("body {}").unwrap();
+    let archive = temp.child("dist.zip");
+
+    let output = ziper(&[path_str(source.path()), path_str(archive.path())]);
+    assert!(output.status.success());
+
+    let out = temp.child("out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    out.child("dist/index.html").assert("<html></html>");
+    out.child("dist/css/style.css").assert("body {}");
+}
+
+#[cfg(unix)]
+#[test]
+fn restores_permissions_and_mtime() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("perm.zip");
+    let mut zip = ZipWriter::new(File::create(archive.path()).unwrap());
+    let options = SimpleFileOptions::default()
+        .unix_permissions(0o640)
+        .last_modified_time(DateTime::from_date_and_time(2020, 1, 2, 3, 4, 6).unwrap());
+    zip.start_file("config.toml", options).unwrap();
+    zip.write_all(b"key = 1").unwrap();
+    zip.start_file("run.sh", options.unix_permissions(0o755))
+        .unwrap();
+    zip.write_all(b"#!/bin/sh").unwrap();
+    zip.finish().unwrap();
+
+    let out = temp.child("out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config = fs::metadata(out.child("config.toml").path()).unwrap();
+    assert_eq!(config.permissions().mode() & 0o777, 0o640);
+    let script = fs::metadata(out.child("run.sh").path()).unwrap();
+    assert_eq!(script.permissions().mode() & 0o777, 0o755);
+
+    // 2020-01-02T03:04:06Z
+    let mtime = config
+        .modified()
+        .unwrap()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap();
+    assert_eq!(mtime.as_secs(), 1577934246);
+}
+
+#[test]
+fn rejects_parent_directory_paths() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("evil.zip");
+    write_zip(archive.path(), &[("ok.txt", "ok"), ("../evil.txt", "evil")]);
+
+    let out = temp.child("out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+
+    assert!(!output.status.success());
+    temp.child("evil.txt").assert(predicate::path::missing());
+    // 检查在写入之前进行，安全的条目也不会被解压
+    out.child("ok.txt").assert(predicate::path::missing());
+}
+
+#[test]
+fn rejects_absolute_paths() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("evil.zip");
+    let target = temp.child("absolute.txt");
+    write_zip(archive.path(), &[(path_str(target.path()), "evil")]);
+
+    let output = ziper(&[
+        "extract",
+        path_str(archive.path()),
+        path_str(temp.child("out").path()),
+    ]);
+
+    assert!(!output.status.success());
+    target.assert(predicate::path::missing());
+}
+
+#[cfg(unix)]
+#[test]
+fn rejects_symlinks_escaping_destination() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("evil.zip");
+    let mut zip = ZipWriter::new(File::create(archive.path()).unwrap());
+    zip.add_symlink("link", "../..", SimpleFileOptions::default())
+        .unwrap();
+    zip.start_file("link/evil.txt", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"evil").unwrap();
+    zip.finish().unwrap();
+
+    let out = temp.child("nested/out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+
+    assert!(!output.status.success());
+    out.child("link").assert(predicate::path::missing());
+    temp.child("evil.txt").assert(predicate::path::missing());
+}
+
+#[cfg(unix)]
+#[test]
+fn rejects_paths_through_existing_symlinks() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("evil.zip");
+    write_zip(archive.path(), &[("link/a/b/evil.txt", "evil")]);
+
+    // 解压之前目标目录中已有指向外部的符号链接
+    let outside = temp.child("outside");
+    outside.create_dir_all().unwrap();
+    let out = temp.child("out");
+    out.create_dir_all().unwrap();
+    std::os::unix::fs::symlink(outside.path(), out.child("link").path()).unwrap();
+
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("拒绝解压到目标目录之外的路径"));
+    outside.child("a").assert(predicate::path::missing());
+}
+
+#[cfg(unix)]
+#[test]
+fn rejects_symlinks_escaping_through_earlier_symlinks() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("evil.zip");
+    let mut zip = ZipWriter::new(File::create(archive.path()).unwrap());
+    // 单独看每个链接都在目标目录内，但 y 经过 d 之后指向目标目录的上一级
+    zip.add_symlink("d", ".", SimpleFileOptions::default())
+        .unwrap();
+    zip.add_symlink("y", "d/..", SimpleFileOptions::default())
+        .unwrap();
+    zip.start_file("y/evil.txt", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"evil").unwrap();
+    zip.finish().unwrap();
+
+    let out = temp.child("out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("拒绝解压指向目标目录之外的符号链接"));
+    out.child("y").assert(predicate::path::missing());
+    temp.child("evil.txt").assert(predicate::path::missing());
+}
+
+#[cfg(unix)]
+#[test]
+fn extracts_symlinks_inside_destination() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("links.zip");
+    let mut zip = ZipWriter::new(File::create(archive.path()).unwrap());
+    zip.start_file("data/file.txt", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"data").unwrap();
+    zip.add_symlink("data/alias.txt", "file.txt", SimpleFileOptions::default())
+        .unwrap();
+    zip.finish().unwrap();
+
+    let out = temp.child("out");
+    let output = ziper(&["extract", path_str(archive.path()), path_str(out.path())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let link = out.child("data/alias.txt");
+    assert_eq!(
+        fs::read_link(link.path()).unwrap(),
+        Path::new("file.txt").to_path_buf()
+    );
+    link.assert("data");
+}
+
+#[test]
+fn conflict_policies() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("a.zip");
+    write_zip(archive.path(), &[("a.txt", "new")]);
+    let out = temp.child("out");
+    out.child("a.txt").write_str("old").unwrap();
+    let extract = |flag: Option<&str>| {
+        let mut args = vec!["extract", path_str(archive.path()), path_str(out.path())];
+        args.extend(flag);
+        ziper(&args)
+    };
+
+    // 默认报错，不修改已存在的文件
+    let output = extract(None);
+    assert!(!output.status.success());
+    out.child("a.txt").assert("old");
+
+    assert!(extract(Some("--skip")).status.success());
+    out.child("a.txt").assert("old");
+
+    assert!(extract(Some("--rename")).status.success());
+    out.child("a.txt").assert("old");
+    out.child("a (1).txt").assert("new");
+
+    assert!(extract(Some("--overwrite")).status.success());
+    out.child("a.txt").assert("new");
+
+    let output = ziper(&[
+        "extract",
+        path_str(archive.path()),
+        path_str(out.path()),
+        "--skip",
+        "--overwrite",
+    ]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn encrypted_round_trip() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("export");
+    source
+        .child("users.csv")
+        .write_str("id,name\n1,a\n")
+        .unwrap();
+    source
+        .child("logo.png")
+        .write_binary(&[0x89, b'P'])
+        .unwrap();
+    let password = temp.child("password.txt");
+    password.write_str("s3cret pass\n").unwrap();
+
+    for method in ["aes256", "zipcrypto"] {
+        let archive = temp.child(format!("{}.zip", method));
+        let output = ziper(&[
+            path_str(source.path()),
+            path_str(archive.path()),
+            "--encrypt",
+            method,
+            "--password-file",
+            path_str(password.path()),
+        ]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        // 没有密码时无法读取内容
+        let mut zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+        assert!(zip.by_name("export/users.csv").is_err());
+        drop(zip);
+
+        let listing = ziper(&["list", path_str(archive.path()), "--format", "json"]);
+        let listing: serde_json::Value = serde_json::from_slice(&listing.stdout).unwrap();
+        let file = listing["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["name"] == "export/users.csv")
+            .unwrap();
+        assert_eq!(file["encryption"], method);
+
+        // 密码也可以从环境变量读取
+        let out = temp.child(format!("out-{}", method));
+        let output = command()
+            .args(["extract", path_str(archive.path()), path_str(out.path())])
+            .env("ZIPER_PASSWORD", "s3cret pass")
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        out.child("export/users.csv").assert("id,name\n1,a\n");
+        out.child("export/logo.png").assert(&[0x89, b'P'][..]);
+    }
+}
+
+#[test]
+fn rejects_wrong_password() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("export");
+    source.child("users.csv").write_str("id,name\n").unwrap();
+    let archive = temp.child("export.zip");
+
+    let output = command()
+        .args([
+            path_str(source.path()),
+            path_str(archive.path()),
+            "--encrypt",
+            "aes256",
+        ])
+        .env("ZIPER_PASSWORD", "right")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let out = temp.child("out");
+    let output = command()
+        .args(["extract", path_str(archive.path()), path_str(out.path())])
+        .env("ZIPER_PASSWORD", "wrong")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("密码错误"));
+    out.child("export/users.csv")
+        .assert(predicate::path::missing());
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use std::fs::File;
+use std::io::Write;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+use common::{path_str, ziper};
+
+#[test]
+fn lists_entries_as_json() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.child("dist.zip");
+    let mut zip = ZipWriter::new(File::create(archive.path()).unwrap());
+    let options = SimpleFileOptions::default()
+        .unix_permissions(0o644)
+        .last_modified_time(DateTime::from_date_and_time(2020, 1, 2, 3, 4, 6).unwrap());
+    zip.add_directory("assets/", options.unix_permissions(0o755))
+        .unwrap();
+    zip.start_file("assets/app.js", options).unwrap();
+    zip.write_all("console.log(1);\n".repeat(100).as_bytes())
+        .unwrap();
+    zip.start_file(
+        "README.md",
+        options.compression_method(CompressionMethod::Stored),
+    )
+    .unwrap();
+    zip.write_all(b"# dist").unwrap();
+    zip.finish().unwrap();
+
+    let output = ziper(&["list", path_str(archive.path()), "--format", "json"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = listing["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+
+    assert_eq!(entries[0]["name"], "assets/");
+    assert_eq!(entries[0]["kind"], "dir");
+
+    let script = &entries[1];
+    assert_eq!(script["kind"], "file");
+    assert_eq!(script["size"], 1600);
+    assert_eq!(script["method"], "Deflated");
+    assert_eq!(script["unix_mode"].as_u64().unwrap() & 0o777, 0o644);
+    assert_eq!(script["modified"], 1577934246);
+    assert!(script["compressed_size"].as_u64().unwrap() < 1600);
+
+    let readme = &entries[2];
+    assert_eq!(readme["method"], "Stored");
+    assert_eq!(readme["size"], 6);
+    assert_eq!(readme["compressed_size"], 6);
+    assert_eq!(readme["crc32"], crc32(b"# dist"));
+
+    assert_eq!(listing["summary"]["entries"], 3);
+    assert_eq!(listing["summary"]["files"], 2);
+}
+
+/// 按 IEEE 多项式计算 CRC32
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
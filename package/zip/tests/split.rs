@@ -0,0 +1,175 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+use common::{path_str, ziper};
+
+/// 几乎无法压缩的伪随机数据
+fn noise(len: usize, seed: u64) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as u8
+        })
+        .collect()
+}
+
+/// 创建约 300K 的源目录
+fn large_source(temp: &TempDir) -> assert_fs::fixture::ChildPath {
+    let source = temp.child("dist");
+    for i in 0..4 {
+        source
+            .child(format!("blob{}.bin", i))
+            .write_binary(&noise(70_000, i))
+            .unwrap();
+    }
+    source.child("docs/readme.txt").write_str("hello").unwrap();
+    source
+}
+
+fn assert_extracted(archive: &Path, out: &Path) {
+    let output = ziper(&["extract", path_str(archive), path_str(out)]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for i in 0..4 {
+        let content = fs::read(out.join(format!("dist/blob{}.bin", i))).unwrap();
+        assert!(content == noise(70_000, i));
+    }
+    assert_eq!(
+        fs::read_to_string(out.join("dist/docs/readme.txt")).unwrap(),
+        "hello"
+    );
+}
+
+#[test]
+fn spanned_volumes_round_trip() {
+    let temp = TempDir::new().unwrap();
+    let source = large_source(&temp);
+    let archive = temp.child("dist.zip");
+
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--split-size",
+        "64K",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let volumes: Vec<_> = (1..=4)
+        .map(|i| temp.child(format!("dist.z{:02}", i)))
+        .collect();
+    for volume in &volumes {
+        assert_eq!(fs::metadata(volume.path()).unwrap().len(), 64 * 1024);
+    }
+    temp.child("dist.z05").assert(predicate::path::missing());
+    assert!(fs::metadata(archive.path()).unwrap().len() <= 64 * 1024);
+    // 第一卷以分卷标记开头
+    assert_eq!(fs::read(volumes[0].path()).unwrap()[..4], *b"PK\x07\x08");
+
+    // 可以指定最后一卷或任意一个分卷
+    assert_extracted(archive.path(), temp.child("out").path());
+    assert_extracted(volumes[1].path(), temp.child("out2").path());
+
+    let listing = ziper(&["list", path_str(archive.path()), "--format", "json"]);
+    assert!(listing.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&listing.stdout).unwrap();
+    assert_eq!(listing["summary"]["files"], 5);
+}
+
+#[test]
+fn numbered_parts_round_trip() {
+    let temp = TempDir::new().unwrap();
+    let source = large_source(&temp);
+    let archive = temp.child("dist.zip");
+
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--split-size",
+        "100K",
+        "--split-mode",
+        "numbered",
+    ]);
+    assert!(output.status.success());
+    archive.assert(predicate::path::missing());
+
+    // 按顺序拼接即为完整的压缩包
+    let mut joined = Vec::new();
+    for i in 1..=3 {
+        joined.extend(fs::read(temp.child(format!("dist.zip.{:03}", i)).path()).unwrap());
+    }
+    temp.child("dist.zip.004")
+        .assert(predicate::path::missing());
+    let zip = zip::ZipArchive::new(std::io::Cursor::new(joined)).unwrap();
+    assert_eq!(zip.len(), 7);
+
+    assert_extracted(temp.child("dist.zip.001").path(), temp.child("out").path());
+    assert_extracted(archive.path(), temp.child("out2").path());
+}
+
+#[test]
+fn small_archive_is_not_split() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source
+        .child("index.html")
+        .write_str("<html></html>")
+        .unwrap();
+    let archive = temp.child("dist.zip");
+    // 上次运行留下的分卷
+    temp.child("dist.z01").write_str("stale").unwrap();
+
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--split-size",
+        "1M",
+    ]);
+    assert!(output.status.success());
+    temp.child("dist.z01").assert(predicate::path::missing());
+
+    let zip = zip::ZipArchive::new(fs::File::open(archive.path()).unwrap()).unwrap();
+    assert_eq!(zip.len(), 2);
+}
+
+#[test]
+fn tar_is_split_into_numbered_parts() {
+    let temp = TempDir::new().unwrap();
+    let source = large_source(&temp);
+    let archive = temp.child("dist.tar");
+
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--split-size",
+        "200K",
+    ]);
+    assert!(output.status.success());
+    temp.child("dist.tar.001").assert(predicate::path::exists());
+    temp.child("dist.tar.002").assert(predicate::path::exists());
+
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--split-size",
+        "200K",
+        "--split-mode",
+        "spanned",
+    ]);
+    assert!(!output.status.success());
+}
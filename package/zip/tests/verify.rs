@@ -0,0 +1,181 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+use common::{path_str, ziper};
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// 创建包含一个较大文本文件的压缩包
+fn create(temp: &TempDir, name: &str, extra: &[&str]) -> assert_fs::fixture::ChildPath {
+    let source = temp.child("dist");
+    let text: String = (0..5000).map(|i| format!("line {}\n", i)).collect();
+    source.child("a.txt").write_str(&text).unwrap();
+    source.child("sub/b.txt").write_str("hello").unwrap();
+    let archive = temp.child(name);
+
+    let mut args = vec![path_str(source.path()), path_str(archive.path())];
+    args.extend(extra);
+    let output = ziper(&args);
+    assert!(output.status.success(), "{}", stderr(&output));
+    archive
+}
+
+/// 修改 `dist/a.txt` 的本地文件头之后第 `offset` 个字节
+fn corrupt_entry(archive: &Path, offset: usize) {
+    let mut data = fs::read(archive).unwrap();
+    let name = data
+        .windows(10)
+        .position(|window| window == b"dist/a.txt")
+        .unwrap();
+    data[name - 30 + offset] ^= 0xFF;
+    fs::write(archive, data).unwrap();
+}
+
+#[test]
+fn valid_archives_pass() {
+    let temp = TempDir::new().unwrap();
+    for name in ["dist.zip", "dist.tar.gz", "dist.tar.zst", "dist.tar.xz"] {
+        let archive = create(&temp, name, &[]);
+        let output = ziper(&["test", path_str(archive.path())]);
+        assert!(output.status.success(), "{}: {}", name, stderr(&output));
+        assert!(stderr(&output).contains("没有发现错误: 4 个条目"));
+    }
+}
+
+#[test]
+fn detects_corrupted_data() {
+    let temp = TempDir::new().unwrap();
+    let archive = create(&temp, "dist.zip", &["--method", "store"]);
+    corrupt_entry(archive.path(), 30 + 10 + 100);
+
+    let output = ziper(&["test", path_str(archive.path())]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("dist/a.txt: 数据损坏"), "{}", stderr);
+    assert!(stderr.contains("发现 1 个损坏的条目"));
+}
+
+#[test]
+fn detects_local_header_mismatch() {
+    let temp = TempDir::new().unwrap();
+    let archive = create(&temp, "dist.zip", &[]);
+    // 本地文件头中的 CRC32
+    corrupt_entry(archive.path(), 14);
+
+    let output = ziper(&["test", path_str(archive.path())]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("dist/a.txt: 本地文件头中的 CRC32 与中央目录不一致"));
+}
+
+#[test]
+fn detects_truncated_archives() {
+    let temp = TempDir::new().unwrap();
+    for name in ["dist.zip", "dist.tar.gz"] {
+        let archive = create(&temp, name, &[]);
+        let data = fs::read(archive.path()).unwrap();
+        fs::write(archive.path(), &data[..data.len() - 10]).unwrap();
+
+        let output = ziper(&["test", path_str(archive.path())]);
+        assert!(!output.status.success(), "{}", name);
+    }
+}
+
+#[test]
+fn tests_encrypted_archives() {
+    let temp = TempDir::new().unwrap();
+    let password = temp.child("secret.txt");
+    password.write_str("correct horse\n").unwrap();
+    let archive = create(
+        &temp,
+        "dist.zip",
+        &[
+            "--encrypt",
+            "aes256",
+            "--password-file",
+            path_str(password.path()),
+        ],
+    );
+
+    let output = ziper(&[
+        "test",
+        path_str(archive.path()),
+        "--password-file",
+        path_str(password.path()),
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    password.write_str("wrong\n").unwrap();
+    let output = ziper(&[
+        "test",
+        path_str(archive.path()),
+        "--password-file",
+        path_str(password.path()),
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("密码错误"));
+}
+
+#[test]
+fn verify_after_create() {
+    let temp = TempDir::new().unwrap();
+    for name in ["dist.zip", "dist.tar.bz2"] {
+        let source = temp.child("dist");
+        source
+            .child("index.html")
+            .write_str("<html></html>")
+            .unwrap();
+        let archive = temp.child(name);
+
+        let output = ziper(&[
+            path_str(source.path()),
+            path_str(archive.path()),
+            "--verify",
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert!(stderr(&output).contains("校验通过: 2 个条目"));
+        archive.assert(predicates::path::exists());
+    }
+}
+
+#[test]
+fn verify_checks_written_volumes() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    // 几乎无法压缩的数据，确保拆分为多个分卷
+    let mut state = 1u64;
+    let noise: Vec<u8> = (0..200_000)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as u8
+        })
+        .collect();
+    source.child("blob.bin").write_binary(&noise).unwrap();
+
+    for mode in ["spanned", "numbered"] {
+        let archive = temp.child(format!("{}.zip", mode));
+        let output = ziper(&[
+            path_str(source.path()),
+            path_str(archive.path()),
+            "--split-size",
+            "64K",
+            "--split-mode",
+            mode,
+            "--verify",
+        ]);
+        assert!(output.status.success(), "{}: {}", mode, stderr(&output));
+        assert!(stderr(&output).contains("校验通过: 2 个条目"));
+    }
+    temp.child("spanned.z01").assert(predicates::path::exists());
+    temp.child("numbered.zip.001")
+        .assert(predicates::path::exists());
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+use common::{path_str, ziper};
+
+/// 压缩包中是否有 ZIP64 结尾记录
+fn has_zip64_end_record(archive: &Path) -> bool {
+    let data = fs::read(archive).unwrap();
+    data.windows(4).any(|window| window == b"PK\x06\x06")
+}
+
+#[test]
+#[ignore = "耗时较长，在 CI 中单独运行：cargo test --test zip64 -- --ignored"]
+fn large_sparse_file_uses_zip64() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.create_dir_all().unwrap();
+    // 稀疏文件不占用磁盘空间，全零的内容压缩后很小
+    let size = (4 << 30) + (1 << 20);
+    File::create(source.child("huge.bin").path())
+        .unwrap()
+        .set_len(size)
+        .unwrap();
+    source.child("z.txt").write_str("after").unwrap();
+    let archive = temp.child("dist.zip");
+
+    let output = ziper(&[
+        path_str(source.path()),
+        path_str(archive.path()),
+        "--method",
+        "zstd",
+        "--level",
+        "1",
+        "-q",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+    assert_eq!(zip.len(), 3);
+    let huge = zip.by_name("dist/huge.bin").unwrap();
+    assert_eq!(huge.size(), size);
+    assert!(huge.compressed_size() < size / 1000);
+    drop(huge);
+    let mut content = String::new();
+    zip.by_name("dist/z.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!(content, "after");
+
+    let listing = ziper(&["list", path_str(archive.path()), "--format", "json"]);
+    assert!(listing.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&listing.stdout).unwrap();
+    assert_eq!(listing["entries"][1]["size"], size);
+}
+
+#[test]
+#[ignore = "耗时较长，在 CI 中单独运行：cargo test --test zip64 -- --ignored"]
+fn many_entries_use_zip64_end_record() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.child("dist");
+    source.create_dir_all().unwrap();
+    let count = 0x10000;
+    for i in 0..count {
+        fs::write(source.path().join(format!("{:05}.txt", i)), i.to_string()).unwrap();
+    }
+    let archive = temp.child("dist.zip");
+
+    let output = ziper(&[path_str(source.path()), path_str(archive.path()), "-q"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(has_zip64_end_record(archive.path()));
+
+    let mut zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+    assert_eq!(zip.len(), count + 1);
+    let mut content = String::new();
+    zip.by_name("dist/65535.txt")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!(content, "65535");
+
+    let out = temp.child("out");
+    let output = ziper(&[
+        "extract",
+        path_str(archive.path()),
+        path_str(out.path()),
+        "-q",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_dir(out.child("dist").path()).unwrap().count(),
+        count
+    );
+}